@@ -1,13 +1,24 @@
 pub mod omega_uint;
 pub mod omega_int;
+pub mod safe_omega_int;
 pub mod ztensor_traits;
 pub mod ztensor_impls;
 pub mod generic_index;
+pub mod memo_ztensor;
+pub mod overlay_ztensor;
+pub mod macros;
 #[cfg(feature = "to-nalgebra")]
 pub mod to_nalg_mat;
+#[cfg(feature = "rayon")]
+pub mod par_ztensor;
 
 pub use omega_int::*;
+pub use safe_omega_int::*;
 pub use omega_uint::*;
 pub use ztensor_traits::*;
 pub use ztensor_impls::*;
 pub use generic_index::*;
+pub use memo_ztensor::*;
+pub use overlay_ztensor::*;
+#[cfg(feature = "rayon")]
+pub use par_ztensor::*;
@@ -0,0 +1,87 @@
+use super::ztensor_impls::{Elem, ZMatrix};
+
+/// Conversion of literal values into tensor elements.
+///
+/// This is used by the construction macros so that integer, real and
+/// complex literals can be mixed freely.
+pub trait IntoElem {
+    /// Converts the value into an element.
+    fn into_elem(self) -> Elem;
+}
+
+impl IntoElem for Elem {
+    fn into_elem(self) -> Elem {
+        self
+    }
+}
+
+impl IntoElem for f32 {
+    fn into_elem(self) -> Elem {
+        self.into()
+    }
+}
+
+impl IntoElem for f64 {
+    fn into_elem(self) -> Elem {
+        (self as f32).into()
+    }
+}
+
+impl IntoElem for i32 {
+    fn into_elem(self) -> Elem {
+        (self as f32).into()
+    }
+}
+
+impl IntoElem for i64 {
+    fn into_elem(self) -> Elem {
+        (self as f32).into()
+    }
+}
+
+/// Builds a finite ZMatrix from rows of elements.
+///
+/// This is the implementation of `zmatrix!`. The resulting ranges start from 0.
+/// Panics if the rows do not all have the same length.
+#[doc(hidden)]
+pub fn zmatrix_from_rows(rows: Vec<Vec<Elem>>) -> ZMatrix {
+    let nrows = rows.len();
+    let ncols = rows.first().map_or(0, |r| r.len());
+    for (i, row) in rows.iter().enumerate() {
+        assert!(row.len() == ncols, "zmatrix! row {} has {} elements, expected {}", i, row.len(), ncols);
+    }
+    ZMatrix::from_flat(rows.into_iter().flatten().collect(), [nrows, ncols])
+}
+
+/// Creates a finite ZMatrix from a list of rows.
+///
+/// Elements may be integers, reals or complex numbers, and are converted
+/// to `Elem`. The ranges of the matrix start from 0. Panics if the rows
+/// have different lengths.
+#[macro_export]
+macro_rules! zmatrix {
+    ($([$($x:expr),* $(,)?]),+ $(,)?) => {
+        $crate::macros::zmatrix_from_rows(vec![$(vec![$($crate::macros::IntoElem::into_elem($x)),*]),+])
+    };
+}
+
+#[test]
+fn test_zmatrix_macro(){
+    use super::ztensor_traits::*;
+    use num_complex::Complex;
+    let m = zmatrix![[1, 2], [3, 4]];
+    assert_eq!(m.get_index_ranges(), [OmegaIndex::Integer(0)..OmegaIndex::Integer(2), OmegaIndex::Integer(0)..OmegaIndex::Integer(2)]);
+    assert_eq!(m.get_single_elem(&[0, 1]), Elem::new(2.0, 0.0));
+    assert_eq!(m.get_single_elem(&[1, 0]), Elem::new(3.0, 0.0));
+
+    let m = zmatrix![[0.5, Complex::new(0.0, 1.0), 2], [-1, 1.5, 0]];
+    assert_eq!(m.get_index_ranges()[1], OmegaIndex::Integer(0)..OmegaIndex::Integer(3));
+    assert_eq!(m.get_single_elem(&[0, 1]), Elem::new(0.0, 1.0));
+    assert_eq!(m.get_single_elem(&[1, 1]), Elem::new(1.5, 0.0));
+}
+
+#[test]
+#[should_panic(expected = "zmatrix! row 1 has 1 elements, expected 2")]
+fn test_zmatrix_macro_ragged_rows(){
+    let _ = zmatrix![[1, 2], [3]];
+}
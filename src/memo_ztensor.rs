@@ -0,0 +1,249 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+#[cfg(feature = "lru")]
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::rc::Rc;
+
+#[cfg(feature = "lru")]
+use lru::LruCache;
+
+use super::ztensor_impls::{BackingKind, Elem, ZTensor};
+use super::ztensor_traits::*;
+
+/// A tensor wrapper that caches every evaluated element.
+///
+/// Each element is computed by the inner tensor on first access and served
+/// from the cache afterwards. The cache is unbounded, so prefer LruZTensor
+/// when many distinct indices are accessed.
+#[derive(Clone)]
+pub struct MemoZTensor<const N: usize> {
+    /// The tensor whose elements are being cached
+    inner: ZTensor<N>,
+    /// Cache of already evaluated elements, keyed by index
+    cache: RefCell<HashMap<[FiniteIndex; N], Elem>>
+}
+
+impl<const N: usize> MemoZTensor<N> {
+    /// Returns the number of elements currently held in the cache.
+    pub fn cache_len(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+    /// Consumes the wrapper and returns the underlying tensor.
+    pub fn into_inner(self) -> ZTensor<N> {
+        self.inner
+    }
+}
+
+/// Implementation of ZTensorLike trait for MemoZTensor.
+///
+/// Elements are served from the cache when present, and computed by the
+/// inner tensor and inserted into the cache otherwise.
+impl<const N: usize> ZTensorLike<N> for MemoZTensor<N> {
+    type DType = Elem;
+
+    fn get_index_ranges(&self) -> [Range<OmegaIndex>; N] {
+        self.inner.get_index_ranges()
+    }
+
+    fn get_single_elem(&self, indices: &[FiniteIndex; N]) -> Self::DType {
+        if let Some(value) = self.cache.borrow().get(indices) {
+            return *value;
+        }
+        let value = self.inner.get_single_elem(indices);
+        self.cache.borrow_mut().insert(*indices, value);
+        value
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Wraps this tensor in an unbounded cache of evaluated elements.
+    ///
+    /// # Returns
+    ///
+    /// A MemoZTensor serving the same elements as this tensor
+    pub fn memoized(self) -> MemoZTensor<N> {
+        MemoZTensor {inner: self, cache: RefCell::new(HashMap::new())}
+    }
+}
+
+#[test]
+fn test_memoized(){
+    use super::omega_int::OmegaInt::*;
+    use std::cell::Cell;
+    let calls = Rc::new(Cell::new(0));
+    let calls_in_getter = calls.clone();
+    let t = ZTensor::<2>::from_ranges_values(&[Integer(0)..Integer(3), MOmega..POmega], move |&[i, j]|{
+        calls_in_getter.set(calls_in_getter.get() + 1);
+        Elem::new(i as f32, j as f32)
+    });
+    let m = t.memoized();
+    for _ in 0..3 {
+        for i in 0..3 {
+            for j in -2..2 {
+                assert_eq!(m.get_single_elem(&[i, j]), Elem::new(i as f32, j as f32));
+            }
+        }
+    }
+    assert_eq!(calls.get(), 12);
+    assert_eq!(m.cache_len(), 12);
+    assert_eq!(m.get_index_ranges(), [Integer(0)..Integer(3), MOmega..POmega]);
+}
+
+/// A tensor wrapper that caches evaluated elements in a bounded LRU cache.
+///
+/// At most `capacity` elements are kept at any time. When the cache is full,
+/// the least recently used element is evicted to make room for a new one,
+/// so memory stays bounded even for access patterns touching many indices.
+#[cfg(feature = "lru")]
+#[derive(Clone)]
+pub struct LruZTensor<const N: usize> {
+    /// The tensor whose elements are being cached
+    inner: ZTensor<N>,
+    /// Cache of already evaluated elements, keyed by index
+    cache: RefCell<LruCache<[FiniteIndex; N], Elem>>
+}
+
+#[cfg(feature = "lru")]
+impl<const N: usize> LruZTensor<N> {
+    /// Returns the maximum number of elements held in the cache.
+    pub fn capacity(&self) -> usize {
+        self.cache.borrow().cap().get()
+    }
+
+    /// Returns the number of elements currently held in the cache.
+    pub fn cache_len(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+    /// Consumes the wrapper and returns the underlying tensor.
+    pub fn into_inner(self) -> ZTensor<N> {
+        self.inner
+    }
+}
+
+/// Implementation of ZTensorLike trait for LruZTensor.
+///
+/// Elements are served from the cache when present, and computed by the
+/// inner tensor and inserted into the cache otherwise.
+#[cfg(feature = "lru")]
+impl<const N: usize> ZTensorLike<N> for LruZTensor<N> {
+    type DType = Elem;
+
+    fn get_index_ranges(&self) -> [Range<OmegaIndex>; N] {
+        self.inner.get_index_ranges()
+    }
+
+    fn get_single_elem(&self, indices: &[FiniteIndex; N]) -> Self::DType {
+        if let Some(value) = self.cache.borrow_mut().get(indices) {
+            return *value;
+        }
+        let value = self.inner.get_single_elem(indices);
+        self.cache.borrow_mut().put(*indices, value);
+        value
+    }
+}
+
+#[cfg(feature = "lru")]
+impl<const N: usize> ZTensor<N> {
+    /// Wraps this tensor in an LRU cache of evaluated elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum number of elements kept in the cache, must be positive
+    ///
+    /// # Returns
+    ///
+    /// An LruZTensor serving the same elements as this tensor
+    pub fn memoize_lru(self, capacity: usize) -> LruZTensor<N> {
+        let capacity = NonZeroUsize::new(capacity).expect("LRU cache capacity must be positive");
+        LruZTensor {inner: self, cache: RefCell::new(LruCache::new(capacity))}
+    }
+}
+
+#[cfg(feature = "lru")]
+#[test]
+fn test_memoize_lru(){
+    use super::omega_int::OmegaInt::*;
+    use std::rc::Rc;
+    use std::cell::Cell;
+    let calls = Rc::new(Cell::new(0));
+    let calls_in_getter = calls.clone();
+    let t = ZTensor::<1>::from_ranges_values(&[Integer(0)..Integer(100)], move |&[i]|{
+        calls_in_getter.set(calls_in_getter.get() + 1);
+        Elem::new(i as f32, 0.0)
+    });
+    let m = t.memoize_lru(4);
+    assert_eq!(m.capacity(), 4);
+
+    for i in 0..20 {
+        assert_eq!(m.get_single_elem(&[i]), Elem::new(i as f32, 0.0));
+        assert!(m.cache_len() <= 4);
+    }
+    assert_eq!(m.cache_len(), 4);
+    assert_eq!(calls.get(), 20);
+
+    // Recently used elements are served from the cache
+    assert_eq!(m.get_single_elem(&[19]), Elem::new(19.0, 0.0));
+    assert_eq!(calls.get(), 20);
+    // Evicted elements are recomputed
+    assert_eq!(m.get_single_elem(&[0]), Elem::new(0.0, 0.0));
+    assert_eq!(calls.get(), 21);
+    assert!(m.cache_len() <= 4);
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Creates a new ZTensor whose elements are computed at most once.
+    ///
+    /// The value function is wrapped in an unbounded cache of evaluated
+    /// elements. The cache is shared by all clones of the tensor and by
+    /// tensors derived from it, so each index is computed at most once
+    /// overall.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - Array of ranges for each dimension
+    /// * `value_getter` - Function that computes the tensor element for given indices
+    ///
+    /// # Returns
+    ///
+    /// A new ZTensor with a memoized value function
+    pub fn from_ranges_values_cached<F: CloneableFn<N, Elem> + 'static>(ranges: &[Range<OmegaIndex>; N], value_getter: F) -> ZTensor<N> {
+        let value_getter: Box<dyn CloneableFn<N, Elem>> = Box::new(value_getter);
+        let cache: Rc<RefCell<HashMap<[FiniteIndex; N], Elem>>> = Rc::new(RefCell::new(HashMap::new()));
+        ZTensor::from_ranges_values(ranges, move |idx: &[FiniteIndex; N]|{
+            if let Some(value) = cache.borrow().get(idx) {
+                return *value;
+            }
+            let value = value_getter(idx);
+            cache.borrow_mut().insert(*idx, value);
+            value
+        }).with_backing(BackingKind::Memoized)
+    }
+}
+
+#[test]
+fn test_from_ranges_values_cached(){
+    use super::omega_int::OmegaInt::*;
+    use std::cell::Cell;
+    let calls = Rc::new(Cell::new(0));
+    let calls_in_getter = calls.clone();
+    let t = ZTensor::<2>::from_ranges_values_cached(&[Integer(0)..Integer(3), MOmega..POmega], move |&[i, j]|{
+        calls_in_getter.set(calls_in_getter.get() + 1);
+        Elem::new(i as f32, j as f32)
+    });
+    for _ in 0..3 {
+        for i in 0..3 {
+            for j in -2..2 {
+                assert_eq!(t.get_single_elem(&[i, j]), Elem::new(i as f32, j as f32));
+            }
+        }
+    }
+    assert_eq!(calls.get(), 12);
+    assert_eq!(t.backing(), BackingKind::Memoized);
+    // Clones and derived tensors share the cache
+    let derived = t.clone().conj_trans();
+    assert_eq!(derived.get_single_elem(&[1, 2]), Elem::new(2.0, -1.0));
+    assert_eq!(calls.get(), 12);
+}
@@ -2,9 +2,12 @@
 /// These special values are used to represent positive and negative infinity, respectively.
 /// The module also implements various traits for OmegaInt, including arithmetic operations and comparisons.
 
-use std::ops::{Add, Div, Mul, Neg, Sub, Rem};
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign, Rem};
 
-use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Signed, One, Zero, Num};
+use num_traits::{Bounded, CheckedAdd, CheckedDiv, CheckedEuclid, CheckedMul, CheckedRem, CheckedSub, FromPrimitive, Pow, Signed, One, ToPrimitive, Zero, Num};
 
 // Unsigned integers which can be infinity
 
@@ -25,7 +28,7 @@ pub trait PMOmega: Sized {
 
 /// This enum represents an integer value, positive infinity (POmega), or negative infinity (MOmega).
 /// The enum is generic over a type N, which represents the integer type.
-#[derive(PartialEq, Clone, Copy, Debug)]
+#[derive(PartialEq, Clone, Copy, Debug, Hash)]
 pub enum OmegaInt<N> {
     Integer(N),
     /// Represents positive infinity.
@@ -51,6 +54,167 @@ impl<N> PMOmega for OmegaInt<N> {
     }
 }
 
+impl<N: Eq> Eq for OmegaInt<N> {}
+
+/// Orders OmegaInt values on the extended integer line, `MOmega < Integer(x) < POmega`.
+impl<N: PartialOrd> PartialOrd for OmegaInt<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Integer(x), Integer(y)) => x.partial_cmp(y),
+            (POmega, POmega) | (MOmega, MOmega) => Some(Ordering::Equal),
+            (MOmega, _) | (_, POmega) => Some(Ordering::Less),
+            (POmega, _) | (_, MOmega) => Some(Ordering::Greater)
+        }
+    }
+}
+
+/// Totally orders OmegaInt values, so `clamp` accepts `MOmega` or `POmega`
+/// as a bound to leave the value unbounded on that side.
+impl<N: Ord> Ord for OmegaInt<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Integer(x), Integer(y)) => x.cmp(y),
+            (POmega, POmega) | (MOmega, MOmega) => Ordering::Equal,
+            (MOmega, _) | (_, POmega) => Ordering::Less,
+            (POmega, _) | (_, MOmega) => Ordering::Greater
+        }
+    }
+}
+
+/// POmega and MOmega are the largest and smallest values of every OmegaInt.
+impl<N> Bounded for OmegaInt<N> {
+    fn min_value() -> Self {
+        MOmega
+    }
+    fn max_value() -> Self {
+        POmega
+    }
+}
+
+/// Conversion of finite values to primitives, delegating to the inner value.
+/// The infinities have no primitive representation and convert to None.
+impl<N: ToPrimitive> ToPrimitive for OmegaInt<N> {
+    fn to_i64(&self) -> Option<i64> {
+        self.as_finite()?.to_i64()
+    }
+    fn to_u64(&self) -> Option<u64> {
+        self.as_finite()?.to_u64()
+    }
+    fn to_i128(&self) -> Option<i128> {
+        self.as_finite()?.to_i128()
+    }
+    fn to_u128(&self) -> Option<u128> {
+        self.as_finite()?.to_u128()
+    }
+    fn to_f32(&self) -> Option<f32> {
+        self.as_finite()?.to_f32()
+    }
+    fn to_f64(&self) -> Option<f64> {
+        self.as_finite()?.to_f64()
+    }
+}
+
+/// Conversion of primitives to finite values, delegating to the inner type.
+impl<N: FromPrimitive> FromPrimitive for OmegaInt<N> {
+    fn from_i64(n: i64) -> Option<Self> {
+        N::from_i64(n).map(Integer)
+    }
+    fn from_u64(n: u64) -> Option<Self> {
+        N::from_u64(n).map(Integer)
+    }
+    fn from_i128(n: i128) -> Option<Self> {
+        N::from_i128(n).map(Integer)
+    }
+    fn from_u128(n: u128) -> Option<Self> {
+        N::from_u128(n).map(Integer)
+    }
+    fn from_f32(n: f32) -> Option<Self> {
+        N::from_f32(n).map(Integer)
+    }
+    fn from_f64(n: f64) -> Option<Self> {
+        N::from_f64(n).map(Integer)
+    }
+}
+
+/// Displays POmega as `ω`, MOmega as `-ω` and finite values with their own Display.
+impl<N: fmt::Display> fmt::Display for OmegaInt<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Integer(x) => x.fmt(f),
+            POmega => write!(f, "ω"),
+            MOmega => write!(f, "-ω")
+        }
+    }
+}
+
+/// Serializes finite values as the bare integer and the infinities as the strings `"POmega"` and `"MOmega"`.
+///
+/// Formats that are not human readable, such as bincode, cannot tell an
+/// integer from a string without a tag, so they get the externally tagged
+/// enum encoding instead.
+#[cfg(feature = "serde")]
+impl<N: serde::Serialize> serde::Serialize for OmegaInt<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match (self, serializer.is_human_readable()) {
+            (Integer(x), true) => x.serialize(serializer),
+            (Integer(x), false) => serializer.serialize_newtype_variant("OmegaInt", 0, "Integer", x),
+            (POmega, true) => serializer.serialize_str("POmega"),
+            (POmega, false) => serializer.serialize_unit_variant("OmegaInt", 1, "POmega"),
+            (MOmega, true) => serializer.serialize_str("MOmega"),
+            (MOmega, false) => serializer.serialize_unit_variant("OmegaInt", 2, "MOmega")
+        }
+    }
+}
+
+/// Encodings of OmegaInt read by its Deserialize implementation.
+#[cfg(feature = "serde")]
+mod omega_int_serde {
+    /// Marker strings standing for the infinities.
+    #[derive(serde::Deserialize)]
+    pub(super) enum Infinity {
+        POmega,
+        MOmega
+    }
+
+    /// Human-readable encoding, a bare integer or a marker string.
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum Bare<N> {
+        Integer(N),
+        Infinity(Infinity)
+    }
+
+    /// Externally tagged encoding used by formats that are not human readable.
+    #[derive(serde::Deserialize)]
+    #[serde(rename = "OmegaInt")]
+    pub(super) enum Tagged<N> {
+        Integer(N),
+        POmega,
+        MOmega
+    }
+}
+
+/// Deserializes the encodings written by the Serialize implementation.
+#[cfg(feature = "serde")]
+impl<'de, N: serde::Deserialize<'de>> serde::Deserialize<'de> for OmegaInt<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use omega_int_serde::*;
+        if deserializer.is_human_readable() {
+            Ok(match Bare::deserialize(deserializer)? {
+                Bare::Integer(x) => Integer(x),
+                Bare::Infinity(Infinity::POmega) => POmega,
+                Bare::Infinity(Infinity::MOmega) => MOmega
+            })
+        } else {
+            Ok(match Tagged::deserialize(deserializer)? {
+                Tagged::Integer(x) => Integer(x),
+                Tagged::POmega => POmega,
+                Tagged::MOmega => MOmega
+            })
+        }
+    }
+}
+
 /// This trait defines the behavior of a type that can provide its sign.
 pub trait GetSign {
     fn get_sign(&self) -> Sign;
@@ -152,6 +316,15 @@ impl<N: CheckedAdd + PrimGetSign> Add for OmegaInt<N> {
     }
 }
 
+impl<N: CheckedAdd + PrimGetSign> AddAssign for OmegaInt<N> {
+    fn add_assign(&mut self, rhs: Self) {
+        match self.checked_add(&rhs) {
+            Some(y) => *self = y,
+            None => panic!("Overflow!")
+        }
+    }
+}
+
 impl<N: CheckedAdd + PrimGetSign> CheckedAdd for OmegaInt<N> {
     fn checked_add(&self, v: &Self) -> Option<Self> {
         fn omega_checker<N: CheckedAdd + PrimGetSign>(s0: Sign, s1: Sign) -> Option<OmegaInt<N>>{
@@ -177,7 +350,7 @@ impl<N: CheckedAdd + PrimGetSign> CheckedAdd for OmegaInt<N> {
                 return Some(POmega);
             }
             if (s0 == -1) & (s1 == -1) {
-                return Some(POmega);
+                return Some(MOmega);
             }
             return None;
         }
@@ -200,6 +373,15 @@ impl<N: CheckedSub + PrimGetSign> Sub for OmegaInt<N> {
     }
 }
 
+impl<N: CheckedSub + PrimGetSign> SubAssign for OmegaInt<N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        match self.checked_sub(&rhs) {
+            Some(y) => *self = y,
+            None => panic!("Overflow!")
+        }
+    }
+}
+
 impl<N: CheckedSub + PrimGetSign> CheckedSub for OmegaInt<N> {
     fn checked_sub(&self, v: &Self) -> Option<Self> {
         fn omega_checker<N: CheckedSub>(s0: Sign, s1: Sign) -> Option<OmegaInt<N>>{
@@ -225,7 +407,7 @@ impl<N: CheckedSub + PrimGetSign> CheckedSub for OmegaInt<N> {
                 return Some(POmega);
             }
             if (s0 == -1) & (s1 == 1) {
-                return Some(POmega);
+                return Some(MOmega);
             }
             return None;
         }
@@ -248,6 +430,15 @@ impl<N: CheckedMul + PrimGetSign> Mul for OmegaInt<N> {
     }
 }
 
+impl<N: CheckedMul + PrimGetSign> MulAssign for OmegaInt<N> {
+    fn mul_assign(&mut self, rhs: Self) {
+        match self.checked_mul(&rhs) {
+            Some(y) => *self = y,
+            None => panic!("Overflow!")
+        }
+    }
+}
+
 impl<N: CheckedMul + PrimGetSign> CheckedMul for OmegaInt<N> {
     fn checked_mul(&self, v: &Self) -> Option<Self> {
         match self {
@@ -359,6 +550,204 @@ impl<N: CheckedDiv + CheckedAdd + PrimGetSign + Zero> CheckedDiv for OmegaInt<N>
     }
 }
 
+impl<N> OmegaInt<N> {
+    /// Converts to the finite value, if any.
+    ///
+    /// # Returns
+    ///
+    /// `Some(x)` for `Integer(x)`, and None for POmega and MOmega
+    pub fn to_finite(self) -> Option<N> {
+        match self {
+            Integer(x) => Some(x),
+            _ => None
+        }
+    }
+
+    /// Checks whether this is a finite integer.
+    ///
+    /// # Returns
+    ///
+    /// True for `Integer(_)`, false for POmega and MOmega
+    pub fn is_finite(&self) -> bool {
+        matches!(self, Integer(_))
+    }
+
+    /// Checks whether this is one of the infinities.
+    ///
+    /// # Returns
+    ///
+    /// True for POmega and MOmega, false for `Integer(_)`
+    pub fn is_infinite(&self) -> bool {
+        !self.is_finite()
+    }
+
+    /// Borrows the finite value, if any.
+    ///
+    /// # Returns
+    ///
+    /// `Some(&x)` for `Integer(x)`, and None for POmega and MOmega
+    pub fn as_finite(&self) -> Option<&N> {
+        match self {
+            Integer(x) => Some(x),
+            _ => None
+        }
+    }
+}
+
+impl<N: PrimGetSign> OmegaInt<N> {
+    /// Returns the infinity with the given sign, which must not be zero.
+    fn omega_with_sign(sign: Sign) -> Self {
+        match sign {
+            1 => POmega,
+            -1 => MOmega,
+            _ => panic!()
+        }
+    }
+
+    /// Adds two values, saturating finite overflow to POmega or MOmega.
+    ///
+    /// Panics on `POmega + MOmega`, like the `+` operator.
+    pub fn saturating_add(self, rhs: Self) -> Self where N: CheckedAdd {
+        match self.checked_add(&rhs) {
+            Some(x) => x,
+            None if self.get_sign() == rhs.get_sign() => Self::omega_with_sign(self.get_sign()),
+            None => panic!("Cannot add infinities of opposite signs!")
+        }
+    }
+
+    /// Subtracts two values, saturating finite overflow to POmega or MOmega.
+    ///
+    /// Panics when subtracting an infinity from itself, like the `-` operator.
+    pub fn saturating_sub(self, rhs: Self) -> Self where N: CheckedSub {
+        match self.checked_sub(&rhs) {
+            Some(x) => x,
+            None if self.is_pmomega() == 0 && rhs.is_pmomega() == 0 => match self.get_sign() {
+                0 => Self::omega_with_sign(-rhs.get_sign()),
+                sign => Self::omega_with_sign(sign)
+            },
+            None => panic!("Cannot subtract an infinity from itself!")
+        }
+    }
+
+    /// Multiplies two values, saturating finite overflow to POmega or MOmega.
+    ///
+    /// Panics on zero times an infinity, like the `*` operator.
+    pub fn saturating_mul(self, rhs: Self) -> Self where N: CheckedMul {
+        match self.checked_mul(&rhs) {
+            Some(x) => x,
+            None if self.get_sign() * rhs.get_sign() != 0 => Self::omega_with_sign(self.get_sign() * rhs.get_sign()),
+            None => panic!("Cannot multiply zero by an infinity!")
+        }
+    }
+}
+
+impl<N: CheckedEuclid + PrimGetSign + Zero> OmegaInt<N> {
+    /// Computes the Euclidean quotient, if it is defined.
+    ///
+    /// Finite operands follow the Euclidean division of `N`, so the remainder
+    /// is never negative. A nonnegative finite value divided by an infinity
+    /// has quotient 0 and remainder itself, while a negative one has no
+    /// finite remainder and gives None, as do infinite dividends, zero
+    /// divisors and finite overflow.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The divisor
+    ///
+    /// # Returns
+    ///
+    /// The quotient `q` with `self = q * rhs + self.rem_euclid(rhs)`, or None
+    pub fn div_euclid(&self, rhs: &Self) -> Option<Self> {
+        match (self, rhs) {
+            (Integer(x), Integer(y)) => x.checked_div_euclid(y).map(Integer),
+            (Integer(x), _) if x.get_sign() >= 0 => Some(Integer(N::zero())),
+            _ => None
+        }
+    }
+
+    /// Computes the Euclidean remainder, if it is defined.
+    ///
+    /// See `div_euclid` for the convention on infinite operands.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The divisor
+    ///
+    /// # Returns
+    ///
+    /// The nonnegative remainder, or None
+    pub fn rem_euclid(&self, rhs: &Self) -> Option<Self> where N: Clone {
+        match (self, rhs) {
+            (Integer(x), Integer(y)) => x.checked_rem_euclid(y).map(Integer),
+            (Integer(x), _) if x.get_sign() >= 0 => Some(Integer(x.clone())),
+            _ => None
+        }
+    }
+}
+
+impl<N: CheckedMul + One + Clone> OmegaInt<N> {
+    /// Raises the value to an integer power, returning None on finite overflow.
+    ///
+    /// Any value to the power 0 is `Integer(1)`. For a positive exponent,
+    /// POmega stays POmega, and MOmega is POmega for even exponents and
+    /// MOmega for odd ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `exp` - The exponent
+    ///
+    /// # Returns
+    ///
+    /// The power, or None if a finite base overflows
+    pub fn checked_pow(self, exp: u32) -> Option<Self> {
+        match (self, exp) {
+            (_, 0) => Some(Integer(N::one())),
+            (Integer(x), _) => num_traits::checked_pow(x, exp as usize).map(Integer),
+            (POmega, _) => Some(POmega),
+            (MOmega, _) if exp % 2 == 0 => Some(POmega),
+            (MOmega, _) => Some(MOmega)
+        }
+    }
+}
+
+impl<N: CheckedMul + One + Clone> Pow<u32> for OmegaInt<N> {
+    type Output = Self;
+    fn pow(self, exp: u32) -> Self::Output {
+        match self.checked_pow(exp) {
+            Some(y) => y,
+            None => panic!("Overflow!")
+        }
+    }
+}
+
+/// Sums starting from zero with `saturating_add`, so finite overflow gives an infinity.
+/// Panics if the values include both POmega and MOmega.
+impl<N: CheckedAdd + PrimGetSign + Zero> Sum for OmegaInt<N> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Integer(N::zero()), OmegaInt::saturating_add)
+    }
+}
+
+impl<'a, N: CheckedAdd + PrimGetSign + Zero + Copy + 'a> Sum<&'a OmegaInt<N>> for OmegaInt<N> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// Multiplies starting from one with `saturating_mul`, so finite overflow gives an infinity.
+/// Panics if the values include both zero and an infinity.
+impl<N: CheckedMul + PrimGetSign + One> Product for OmegaInt<N> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Integer(N::one()), OmegaInt::saturating_mul)
+    }
+}
+
+impl<'a, N: CheckedMul + PrimGetSign + One + Copy + 'a> Product<&'a OmegaInt<N>> for OmegaInt<N> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
+
 impl <N: Neg<Output = N>> Neg for OmegaInt<N> {
     type Output = Self;
     fn neg(self) -> Self::Output {
@@ -370,7 +759,7 @@ impl <N: Neg<Output = N>> Neg for OmegaInt<N> {
     }
 }
 
-impl <N: CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + PrimGetSign + Copy + PartialEq + Zero + One + Neg<Output = N> + Rem<Output = N>> Signed for OmegaInt<N> {
+impl <N: Num + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + CheckedRem + PrimGetSign + Copy + Neg<Output = N>> Signed for OmegaInt<N> {
     fn abs(&self) -> Self {
         match GetSign::get_sign(self) {
             1 => self.clone(),
@@ -396,27 +785,59 @@ impl <N: CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + PrimGetSign + Copy
     }
 }
 
-impl<N: PrimGetSign + Rem<Output = N>> Rem for OmegaInt<N> {
+impl<N: CheckedRem> Rem for OmegaInt<N> {
     type Output = Self;
     fn rem(self, rhs: Self) -> Self::Output {
-        let self_value: N;
-        let rhs_value: N;
+        match self.checked_rem(&rhs) {
+            Some(y) => y,
+            None => panic!("Remainder with an infinite operand or a zero divisor!")
+        }
+    }
+}
+
+/// The remainder of two finite values is the remainder of the inner values,
+/// `Integer(a) % Integer(b) == Integer(a % b)`. It is undefined when either
+/// operand is infinite or the divisor is zero.
+impl<N: CheckedRem> CheckedRem for OmegaInt<N> {
+    fn checked_rem(&self, v: &Self) -> Option<Self> {
+        match (self, v) {
+            (Integer(x), Integer(y)) => x.checked_rem(y).map(Integer),
+            _ => None
+        }
+    }
+}
+
+/// Error returned when parsing an OmegaInt from a string fails.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseOmegaIntError<E> {
+    /// The string is empty
+    Empty,
+    /// The string is not an infinity and is not a valid finite value
+    InvalidInteger(E)
+}
+
+impl<E: fmt::Display> fmt::Display for ParseOmegaIntError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Integer(x) => self_value = x,
-            _ => panic!()
-        };
-        match rhs {
-            Integer(x) => rhs_value = x,
-            _ => panic!()
-        };
-        return Integer(self_value % rhs_value);
+            ParseOmegaIntError::Empty => write!(f, "cannot parse an omega integer from an empty string"),
+            ParseOmegaIntError::InvalidInteger(e) => write!(f, "invalid omega integer: {}", e)
+        }
     }
 }
 
-impl <N: CheckedAdd + CheckedMul + CheckedSub + CheckedDiv + Copy + PrimGetSign + PartialEq + Zero + One + Rem<Output = N>> Num for OmegaInt<N> {
-    type FromStrRadixErr = ();
-    fn from_str_radix(_str: &str, _radix: u32) -> Result<Self, Self::FromStrRadixErr> {
-        panic!()
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ParseOmegaIntError<E> {}
+
+/// Parses `ω` or `+ω` as POmega and `-ω` as MOmega, and any other string
+/// as a finite value with `N::from_str_radix`.
+impl <N: Num + CheckedAdd + CheckedMul + CheckedSub + CheckedDiv + CheckedRem + Copy + PrimGetSign> Num for OmegaInt<N> {
+    type FromStrRadixErr = ParseOmegaIntError<N::FromStrRadixErr>;
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        match str {
+            "" => Err(ParseOmegaIntError::Empty),
+            "ω" | "+ω" => Ok(POmega),
+            "-ω" => Ok(MOmega),
+            _ => N::from_str_radix(str, radix).map(Integer).map_err(ParseOmegaIntError::InvalidInteger)
+        }
     }
 }
 
@@ -433,6 +854,231 @@ fn test_omega_int(){
     assert_eq!(x*Integer(-92), MOmega);
 }
 
+#[test]
+fn test_omega_int_add_infinities(){
+    let p: OmegaInt<i32> = POmega;
+    let m: OmegaInt<i32> = MOmega;
+    assert_eq!(m + m, MOmega);
+    assert_eq!(p + p, POmega);
+    assert_eq!(m.checked_add(&m), Some(MOmega));
+    assert_eq!(p.checked_add(&m), None);
+}
+
+#[test]
+fn test_omega_int_sub_infinities(){
+    let p: OmegaInt<i32> = POmega;
+    let m: OmegaInt<i32> = MOmega;
+    assert_eq!(m - p, MOmega);
+    assert_eq!(p - m, POmega);
+    assert_eq!(Integer(3) - p, MOmega);
+    assert_eq!(Integer(3) - m, POmega);
+    assert_eq!(m.checked_sub(&m), None);
+}
+
+#[test]
+fn test_omega_int_rem(){
+    let p: OmegaInt<i32> = POmega;
+    assert_eq!(Integer(7) % Integer(3), Integer(1));
+    assert_eq!(Integer(-7) % Integer(3), Integer(-1));
+    assert_eq!(Integer(7).checked_rem(&Integer(-3)), Some(Integer(1)));
+    assert_eq!(Integer(7).checked_rem(&p), None);
+    assert_eq!(p.checked_rem(&Integer(7)), None);
+    assert_eq!(Integer(7).checked_rem(&Integer(0)), None);
+}
+
+#[test]
+fn test_omega_int_saturating(){
+    let x: OmegaInt<i8> = Integer(100);
+    assert_eq!(x.saturating_add(Integer(27)), Integer(127));
+    assert_eq!(x.saturating_add(x), POmega);
+    assert_eq!((-x).saturating_add(-x), MOmega);
+    assert_eq!(x.saturating_add(MOmega), MOmega);
+    assert_eq!((-x).saturating_sub(x), MOmega);
+    assert_eq!(x.saturating_sub(-x), POmega);
+    assert_eq!(Integer(0).saturating_sub(Integer(i8::MIN)), POmega);
+    assert_eq!(x.saturating_sub(POmega), MOmega);
+    assert_eq!(Integer(20i8).saturating_mul(Integer(20)), POmega);
+    assert_eq!(Integer(-20i8).saturating_mul(Integer(-20)), POmega);
+    assert_eq!(Integer(20i8).saturating_mul(Integer(-20)), MOmega);
+    assert_eq!(Integer(5i8).saturating_mul(Integer(-20)), Integer(-100));
+    assert_eq!(Integer(-1i8).saturating_mul(POmega), MOmega);
+}
+
+#[test]
+fn test_omega_int_bounded(){
+    assert_eq!(OmegaInt::<i32>::min_value(), MOmega);
+    assert_eq!(OmegaInt::<i32>::max_value(), POmega);
+    let values = [Integer(3), Integer(-8), Integer(5)];
+    let max = values.iter().fold(OmegaInt::<i32>::min_value(), |acc, &x| acc.max(x));
+    assert_eq!(max, Integer(5));
+}
+
+#[test]
+fn test_omega_int_to_finite(){
+    assert_eq!(Integer(-4).to_finite(), Some(-4));
+    assert_eq!(Integer(9).as_finite(), Some(&9));
+    assert_eq!(OmegaInt::<i32>::POmega.to_finite(), None);
+    assert_eq!(OmegaInt::<i32>::MOmega.to_finite(), None);
+    assert_eq!(OmegaInt::<i32>::POmega.as_finite(), None);
+    assert_eq!(OmegaInt::<i32>::MOmega.as_finite(), None);
+}
+
+#[test]
+fn test_omega_int_is_finite(){
+    assert!(Integer(0).is_finite());
+    assert!(!Integer(0).is_infinite());
+    assert!(!OmegaInt::<i32>::POmega.is_finite());
+    assert!(OmegaInt::<i32>::POmega.is_infinite());
+    assert!(!OmegaInt::<i32>::MOmega.is_finite());
+    assert!(OmegaInt::<i32>::MOmega.is_infinite());
+}
+
+#[test]
+fn test_omega_int_primitive_conversions(){
+    assert_eq!(OmegaInt::<i64>::POmega.to_i64(), None);
+    assert_eq!(OmegaInt::<i64>::MOmega.to_f32(), None);
+    assert_eq!(Integer(7).to_f64(), Some(7.0));
+    assert_eq!(Integer(-7).to_u64(), None);
+    assert_eq!(OmegaInt::<i8>::from_i64(-5), Some(Integer(-5)));
+    assert_eq!(OmegaInt::<i8>::from_i64(300), None);
+    assert_eq!(OmegaInt::<i32>::from_f64(2.0), Some(Integer(2)));
+}
+
+#[test]
+fn test_omega_int_pow(){
+    let m: OmegaInt<i8> = MOmega;
+    assert_eq!(m.pow(2), POmega);
+    assert_eq!(m.pow(3), MOmega);
+    assert_eq!(m.pow(0), Integer(1));
+    assert_eq!(OmegaInt::<i8>::POmega.pow(5), POmega);
+    assert_eq!(Integer(-3i8).pow(3), Integer(-27));
+    assert_eq!(Integer(2i8).checked_pow(7), None);
+    assert_eq!(Integer(2i8).checked_pow(6), Some(Integer(64)));
+}
+
+#[test]
+fn test_omega_int_clamp(){
+    assert_eq!(Integer(5).clamp(MOmega, Integer(3)), Integer(3));
+    assert_eq!(Integer(5).clamp(Integer(7), POmega), Integer(7));
+    assert_eq!(Integer(5).clamp(MOmega, POmega), Integer(5));
+    assert_eq!(OmegaInt::<i32>::POmega.clamp(Integer(0), Integer(10)), Integer(10));
+    assert_eq!(OmegaInt::<i32>::MOmega.clamp(Integer(0), POmega), Integer(0));
+    assert_eq!(OmegaInt::<i32>::MOmega.clamp(MOmega, Integer(4)), MOmega);
+}
+
+#[test]
+fn test_omega_int_assign_ops(){
+    let mut x: OmegaInt<i64> = Integer(3);
+    x += Integer(4);
+    assert_eq!(x, Integer(7));
+    x -= Integer(10);
+    assert_eq!(x, Integer(-3));
+    x *= Integer(-5);
+    assert_eq!(x, Integer(15));
+    x += MOmega;
+    assert_eq!(x, MOmega);
+    x *= Integer(-2);
+    assert_eq!(x, POmega);
+}
+
+#[test]
+#[should_panic(expected = "Overflow!")]
+fn test_omega_int_add_assign_overflow(){
+    let mut x: OmegaInt<i8> = Integer(100);
+    x += Integer(100);
+}
+
+#[test]
+fn test_omega_int_sum_product(){
+    let values: Vec<OmegaInt<i64>> = vec![Integer(3), Integer(-5), POmega, Integer(10)];
+    assert_eq!(values.iter().sum::<OmegaInt<i64>>(), POmega);
+    assert_eq!(values.iter().product::<OmegaInt<i64>>(), MOmega);
+    assert_eq!(values[..2].iter().sum::<OmegaInt<i64>>(), Integer(-2));
+    assert_eq!(values.into_iter().take(2).product::<OmegaInt<i64>>(), Integer(-15));
+    assert_eq!(std::iter::empty::<OmegaInt<i64>>().sum::<OmegaInt<i64>>(), Integer(0));
+    assert_eq!(std::iter::empty::<OmegaInt<i64>>().product::<OmegaInt<i64>>(), Integer(1));
+    // Finite overflow saturates
+    assert_eq!([Integer(100i8), Integer(100)].into_iter().sum::<OmegaInt<i8>>(), POmega);
+}
+
+#[test]
+fn test_omega_int_euclid(){
+    let p: OmegaInt<i32> = POmega;
+    assert_eq!(Integer(-7).div_euclid(&Integer(3)), Some(Integer(-3)));
+    assert_eq!(Integer(-7).rem_euclid(&Integer(3)), Some(Integer(2)));
+    assert_eq!(Integer(-7).div_euclid(&Integer(-3)), Some(Integer(3)));
+    assert_eq!(Integer(-7).rem_euclid(&Integer(-3)), Some(Integer(2)));
+    assert_eq!(Integer(7).div_euclid(&Integer(0)), None);
+    assert_eq!(Integer(7).div_euclid(&p), Some(Integer(0)));
+    assert_eq!(Integer(7).rem_euclid(&MOmega), Some(Integer(7)));
+    assert_eq!(Integer(-7).div_euclid(&p), None);
+    assert_eq!(Integer(-7).rem_euclid(&p), None);
+    assert_eq!(p.div_euclid(&Integer(2)), None);
+    assert_eq!(Integer(i32::MIN).div_euclid(&Integer(-1)), None);
+}
+
+#[test]
+fn test_omega_int_from_str_radix(){
+    type O = OmegaInt<i32>;
+    assert_eq!(O::from_str_radix("ω", 10), Ok(POmega));
+    assert_eq!(O::from_str_radix("+ω", 10), Ok(POmega));
+    assert_eq!(O::from_str_radix("-ω", 16), Ok(MOmega));
+    assert_eq!(O::from_str_radix("-1f", 16), Ok(Integer(-31)));
+    assert_eq!(O::from_str_radix("", 10), Err(ParseOmegaIntError::Empty));
+    assert!(matches!(O::from_str_radix("omega", 10), Err(ParseOmegaIntError::InvalidInteger(_))));
+    // Displayed values parse back
+    assert_eq!(O::from_str_radix(&O::MOmega.to_string(), 10), Ok(MOmega));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_omega_int_serde(){
+    for (value, json) in [(Integer(-3), "-3"), (POmega, r#""POmega""#), (MOmega, r#""MOmega""#)] {
+        let value: OmegaInt<i64> = value;
+        assert_eq!(serde_json::to_string(&value).unwrap(), json);
+        assert_eq!(serde_json::from_str::<OmegaInt<i64>>(json).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_omega_int_hash(){
+    use std::collections::HashSet;
+    let mut set: HashSet<OmegaInt<i64>> = HashSet::new();
+    assert!(set.insert(POmega));
+    assert!(set.insert(Integer(3)));
+    assert!(!set.insert(Integer(3)));
+    assert!(set.contains(&POmega));
+    assert!(!set.contains(&MOmega));
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn test_omega_int_ord(){
+    let p: OmegaInt<i32> = POmega;
+    let m: OmegaInt<i32> = MOmega;
+    assert_eq!(p.cmp(&POmega), Ordering::Equal);
+    assert_eq!(m.cmp(&MOmega), Ordering::Equal);
+    for x in [i32::MIN, -5, 0, 7, i32::MAX] {
+        assert!(m < Integer(x));
+        assert!(Integer(x) < p);
+        assert!(Integer(x) > m);
+    }
+    assert!(m < p);
+    assert!(Integer(-3) < Integer(2));
+    assert_eq!(Integer(4).cmp(&Integer(4)), Ordering::Equal);
+    assert_eq!(Integer(9).max(Integer(-9)), Integer(9));
+    let mut v = vec![POmega, Integer(3), MOmega, Integer(-1)];
+    v.sort();
+    assert_eq!(v, vec![MOmega, Integer(-1), Integer(3), POmega]);
+}
+
+#[test]
+fn test_omega_int_display(){
+    assert_eq!(OmegaInt::<i32>::POmega.to_string(), "ω");
+    assert_eq!(OmegaInt::<i32>::MOmega.to_string(), "-ω");
+    assert_eq!(Integer(-42).to_string(), "-42");
+    assert_eq!(format!("{:?}", OmegaInt::<i32>::POmega), "POmega");
+}
 
 impl<I> From<I> for OmegaInt<I> {
     fn from(value: I) -> Self {
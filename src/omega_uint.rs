@@ -1,6 +1,10 @@
+use std::cmp::Ordering;
+use std::fmt;
 use std::ops::{Add, Div, Mul, Rem, Sub};
 
-use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Num, One, Unsigned, Zero};
+use num_traits::{Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedRem, CheckedSub, Num, One, Signed, Unsigned, Zero};
+
+use super::omega_int::OmegaInt;
 
 /// Unsigned integers which can be infinity (ω).
 /// This module implements a representation of natural numbers extended with infinity.
@@ -26,7 +30,7 @@ pub trait Omega: Sized {
 ///
 /// This enum represents either a natural number or infinity,
 /// allowing arithmetic operations to work with potentially infinite values.
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy, Debug, Hash)]
 pub enum OmegaUInt<N: Unsigned> {
     /// A regular natural number value
     Natural(N),
@@ -35,6 +39,121 @@ pub enum OmegaUInt<N: Unsigned> {
 }
 use OmegaUInt::*;
 
+impl<N: Unsigned + Eq> Eq for OmegaUInt<N> {}
+
+/// Implementation of PartialOrd for OmegaUInt.
+///
+/// Naturals are ordered as usual, and Omega is larger than all of them.
+impl<N: Unsigned + PartialOrd> PartialOrd for OmegaUInt<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Natural(x), Natural(y)) => x.partial_cmp(y),
+            (Omega, Omega) => Some(Ordering::Equal),
+            (Natural(_), Omega) => Some(Ordering::Less),
+            (Omega, Natural(_)) => Some(Ordering::Greater)
+        }
+    }
+}
+
+/// Implementation of Ord for OmegaUInt, with Omega as the maximum.
+impl<N: Unsigned + Ord> Ord for OmegaUInt<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Natural(x), Natural(y)) => x.cmp(y),
+            (Omega, Omega) => Ordering::Equal,
+            (Natural(_), Omega) => Ordering::Less,
+            (Omega, Natural(_)) => Ordering::Greater
+        }
+    }
+}
+
+/// Implementation of Bounded for OmegaUInt.
+///
+/// Zero is the smallest value and Omega the largest.
+impl<N: Unsigned> Bounded for OmegaUInt<N> {
+    fn min_value() -> Self {
+        Natural(N::zero())
+    }
+    fn max_value() -> Self {
+        Omega
+    }
+}
+
+/// Implementation of Display for OmegaUInt.
+///
+/// Omega is printed as `ω` and naturals with their own Display.
+impl<N: Unsigned + fmt::Display> fmt::Display for OmegaUInt<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Natural(x) => x.fmt(f),
+            Omega => write!(f, "ω")
+        }
+    }
+}
+
+/// Implementation of Serialize for OmegaUInt.
+///
+/// Naturals are written as the bare integer and Omega as the string
+/// `"Omega"`. Formats that are not human readable, such as bincode, cannot
+/// tell the two apart without a tag, so they get the externally tagged enum
+/// encoding instead.
+#[cfg(feature = "serde")]
+impl<N: Unsigned + serde::Serialize> serde::Serialize for OmegaUInt<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match (self, serializer.is_human_readable()) {
+            (Natural(x), true) => x.serialize(serializer),
+            (Natural(x), false) => serializer.serialize_newtype_variant("OmegaUInt", 0, "Natural", x),
+            (Omega, true) => serializer.serialize_str("Omega"),
+            (Omega, false) => serializer.serialize_unit_variant("OmegaUInt", 1, "Omega")
+        }
+    }
+}
+
+/// Encodings of OmegaUInt read by its Deserialize implementation.
+#[cfg(feature = "serde")]
+mod omega_uint_serde {
+    /// Marker string standing for infinity.
+    #[derive(serde::Deserialize)]
+    pub(super) enum Infinity {
+        Omega
+    }
+
+    /// Human-readable encoding, a bare integer or the marker string.
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum Bare<N> {
+        Natural(N),
+        Infinity(Infinity)
+    }
+
+    /// Externally tagged encoding used by formats that are not human readable.
+    #[derive(serde::Deserialize)]
+    #[serde(rename = "OmegaUInt")]
+    pub(super) enum Tagged<N> {
+        Natural(N),
+        Omega
+    }
+}
+
+/// Implementation of Deserialize for OmegaUInt, reading the encodings written by Serialize.
+#[cfg(feature = "serde")]
+impl<'de, N: Unsigned + serde::Deserialize<'de>> serde::Deserialize<'de> for OmegaUInt<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use omega_uint_serde::*;
+        if deserializer.is_human_readable() {
+            Ok(match Bare::deserialize(deserializer)? {
+                Bare::Natural(x) => Natural(x),
+                Bare::Infinity(Infinity::Omega) => Omega
+            })
+        } else {
+            Ok(match Tagged::deserialize(deserializer)? {
+                Tagged::Natural(x) => Natural(x),
+                Tagged::Omega => Omega
+            })
+        }
+    }
+}
+
 /// Implementation of the Omega trait for OmegaUInt.
 impl<N: Unsigned> Omega for OmegaUInt<N> {
     fn is_omega(&self) -> bool{
@@ -65,7 +184,7 @@ impl<N: Unsigned + CheckedAdd> Zero for OmegaUInt<N> {
 }
 
 /// Implementation of Num trait for OmegaUInt.
-impl <N: Unsigned + CheckedAdd + CheckedMul + CheckedSub + CheckedDiv + Copy> Num for OmegaUInt<N> {
+impl <N: Unsigned + CheckedAdd + CheckedMul + CheckedSub + CheckedDiv + CheckedRem + Copy> Num for OmegaUInt<N> {
     type FromStrRadixErr = ();
     fn from_str_radix(_str: &str, _radix: u32) -> Result<Self, Self::FromStrRadixErr> {
         panic!("Not supported!");
@@ -73,7 +192,7 @@ impl <N: Unsigned + CheckedAdd + CheckedMul + CheckedSub + CheckedDiv + Copy> Nu
 }
 
 /// Implementation of Unsigned trait for OmegaUInt.
-impl <N: Unsigned + CheckedAdd + CheckedMul + CheckedSub + CheckedDiv + Copy> Unsigned for OmegaUInt<N> {}
+impl <N: Unsigned + CheckedAdd + CheckedMul + CheckedSub + CheckedDiv + CheckedRem + Copy> Unsigned for OmegaUInt<N> {}
 
 /// Implementation of One trait for OmegaUInt.
 ///
@@ -179,20 +298,25 @@ impl<N: Unsigned + CheckedDiv + Copy> CheckedDiv for OmegaUInt<N> {
 }
 
 /// Implementation of remainder operation for OmegaUInt.
-impl<N: Unsigned> Rem for OmegaUInt<N> {
+impl<N: Unsigned + CheckedRem> Rem for OmegaUInt<N> {
     type Output = Self;
     fn rem(self, rhs: Self) -> Self::Output {
-        let self_value: N;
-        let rhs_value: N;
-        match self {
-            Self::Natural(x) => self_value = x,
-            Self::Omega => panic!()
-        };
-        match rhs {
-            Self::Natural(x) => rhs_value = x,
-            Self::Omega => panic!()
-        };
-        return Self::Natural(self_value % rhs_value);
+        match self.checked_rem(&rhs) {
+            Some(y) => y,
+            None => panic!("Remainder with Omega or a zero divisor!")
+        }
+    }
+}
+
+/// Implementation of checked remainder for OmegaUInt.
+///
+/// The remainder is only defined for naturals with a nonzero divisor.
+impl<N: Unsigned + CheckedRem> CheckedRem for OmegaUInt<N> {
+    fn checked_rem(&self, v: &Self) -> Option<Self> {
+        match (self, v) {
+            (Natural(x), Natural(y)) => x.checked_rem(y).map(Natural),
+            _ => None
+        }
     }
 }
 
@@ -246,6 +370,57 @@ impl<N: Unsigned + CheckedSub> Sub for OmegaUInt<N>{
     }
 }
 
+impl<N: Unsigned> OmegaUInt<N> {
+    /// Converts to the finite value, if any.
+    ///
+    /// # Returns
+    ///
+    /// `Some(x)` for `Natural(x)`, and None for Omega
+    pub fn to_finite(self) -> Option<N> {
+        match self {
+            Natural(x) => Some(x),
+            Omega => None
+        }
+    }
+
+    /// Borrows the finite value, if any.
+    ///
+    /// # Returns
+    ///
+    /// `Some(&x)` for `Natural(x)`, and None for Omega
+    pub fn as_finite(&self) -> Option<&N> {
+        match self {
+            Natural(x) => Some(x),
+            Omega => None
+        }
+    }
+}
+
+impl<N: Unsigned + CheckedSub> OmegaUInt<N> {
+    /// Subtracts, flooring at zero.
+    ///
+    /// Unlike `checked_sub`, which returns None when a finite difference
+    /// would be negative, finite underflow and subtracting Omega from a
+    /// natural give `Natural(0)`. Omega minus a natural is Omega, and
+    /// `Omega - Omega` panics since it is undefined.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value to subtract
+    ///
+    /// # Returns
+    ///
+    /// The difference, floored at zero
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Natural(x), Natural(y)) => Natural(x.checked_sub(&y).unwrap_or_else(N::zero)),
+            (Natural(_), Omega) => Natural(N::zero()),
+            (Omega, Natural(_)) => Omega,
+            (Omega, Omega) => panic!("Cannot subtract Omega from itself!")
+        }
+    }
+}
+
 /// Implementation of checked multiplication for OmegaUInt.
 impl<N: Unsigned + CheckedAdd + CheckedMul> CheckedMul for OmegaUInt<N>{
     fn checked_mul(&self, v: &Self) -> Option<Self> {
@@ -278,3 +453,139 @@ impl<U: Unsigned> From<U> for OmegaUInt<U> {
         OmegaUInt::Natural(value)
     }
 }
+
+/// Error returned when an OmegaInt cannot be converted to an OmegaUInt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryFromOmegaIntError {
+    /// The value is negative, including MOmega
+    Negative,
+    /// The value is finite and nonnegative, but does not fit in the target type
+    OutOfRange
+}
+
+impl fmt::Display for TryFromOmegaIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryFromOmegaIntError::Negative => write!(f, "negative omega integer cannot be converted to an unsigned one"),
+            TryFromOmegaIntError::OutOfRange => write!(f, "omega integer is out of the range of the unsigned type")
+        }
+    }
+}
+
+impl std::error::Error for TryFromOmegaIntError {}
+
+/// Conversion of nonnegative OmegaInt values to OmegaUInt, mapping POmega to Omega.
+impl<N: PartialOrd + Zero, M: Unsigned + TryFrom<N>> TryFrom<OmegaInt<N>> for OmegaUInt<M> {
+    type Error = TryFromOmegaIntError;
+    fn try_from(value: OmegaInt<N>) -> Result<Self, Self::Error> {
+        match value {
+            OmegaInt::POmega => Ok(Omega),
+            OmegaInt::MOmega => Err(TryFromOmegaIntError::Negative),
+            OmegaInt::Integer(x) if x < N::zero() => Err(TryFromOmegaIntError::Negative),
+            OmegaInt::Integer(x) => M::try_from(x).map(Natural).map_err(|_| TryFromOmegaIntError::OutOfRange)
+        }
+    }
+}
+
+/// Conversion of OmegaUInt values to OmegaInt, mapping Omega to POmega.
+impl<N: Unsigned, M: Signed + From<N>> From<OmegaUInt<N>> for OmegaInt<M> {
+    fn from(value: OmegaUInt<N>) -> Self {
+        match value {
+            Natural(x) => OmegaInt::Integer(M::from(x)),
+            Omega => OmegaInt::POmega
+        }
+    }
+}
+
+#[test]
+fn test_omega_uint_clone_debug(){
+    let lengths: [OmegaUInt<u64>; 2] = [Natural(3), Omega];
+    let copied = lengths;
+    assert_eq!(copied, lengths.clone());
+    assert_eq!(format!("{:?}", copied[0]), "Natural(3)");
+    assert_eq!(format!("{:?}", copied[1]), "Omega");
+}
+
+#[test]
+fn test_omega_uint_ord(){
+    let omega: OmegaUInt<u64> = Omega;
+    assert!(Natural(2u64) < Natural(5));
+    assert_eq!(Natural(5u64).cmp(&Natural(5)), Ordering::Equal);
+    assert!(Natural(5u64) < Omega);
+    assert!(Natural(u64::MAX) < Omega);
+    assert!(omega > Natural(0));
+    assert_eq!(omega.cmp(&Omega), Ordering::Equal);
+    let mut lengths = vec![Omega, Natural(7u64), Natural(1)];
+    lengths.sort();
+    assert_eq!(lengths, vec![Natural(1), Natural(7), Omega]);
+    assert_eq!(lengths.iter().max(), Some(&Omega));
+}
+
+#[test]
+fn test_omega_uint_display(){
+    assert_eq!(OmegaUInt::<u32>::Omega.to_string(), "ω");
+    assert_eq!(Natural(42u32).to_string(), "42");
+}
+
+#[test]
+fn test_omega_uint_saturating_sub(){
+    assert_eq!(Natural(3u32).saturating_sub(Natural(5)), Natural(0));
+    assert_eq!(Natural(5u32).saturating_sub(Natural(3)), Natural(2));
+    assert_eq!(OmegaUInt::<u32>::Omega.saturating_sub(Natural(5)), Omega);
+    assert_eq!(Natural(5u32).saturating_sub(Omega), Natural(0));
+}
+
+#[test]
+fn test_omega_uint_rem(){
+    assert_eq!(Natural(7u32) % Natural(3), Natural(1));
+    assert_eq!(Natural(7u32).checked_rem(&Natural(3)), Some(Natural(1)));
+    assert_eq!(OmegaUInt::<u32>::Omega.checked_rem(&Natural(3)), None);
+    assert_eq!(Natural(7u32).checked_rem(&Omega), None);
+    assert_eq!(Natural(7u32).checked_rem(&Natural(0)), None);
+}
+
+#[test]
+fn test_omega_uint_bounded(){
+    assert_eq!(OmegaUInt::<u32>::max_value(), Omega);
+    assert_eq!(OmegaUInt::<u32>::min_value(), Natural(0));
+}
+
+#[test]
+fn test_omega_uint_to_finite(){
+    assert_eq!(Natural(6usize).to_finite(), Some(6));
+    assert_eq!(Natural(6usize).as_finite(), Some(&6));
+    assert_eq!(OmegaUInt::<usize>::Omega.to_finite(), None);
+    assert_eq!(OmegaUInt::<usize>::Omega.as_finite(), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_omega_uint_serde(){
+    for (value, json) in [(Natural(12), "12"), (Omega, r#""Omega""#)] {
+        let value: OmegaUInt<u32> = value;
+        assert_eq!(serde_json::to_string(&value).unwrap(), json);
+        assert_eq!(serde_json::from_str::<OmegaUInt<u32>>(json).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_omega_uint_hash(){
+    use std::collections::HashSet;
+    let set: HashSet<OmegaUInt<u32>> = [Natural(3), Omega, Natural(3)].into_iter().collect();
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&Omega));
+}
+
+#[test]
+fn test_omega_int_uint_conversions(){
+    assert_eq!(OmegaUInt::<u32>::try_from(OmegaInt::Integer(7i64)), Ok(Natural(7)));
+    assert_eq!(OmegaUInt::<u32>::try_from(OmegaInt::<i64>::POmega), Ok(Omega));
+    assert_eq!(OmegaUInt::<u32>::try_from(OmegaInt::Integer(-1i64)), Err(TryFromOmegaIntError::Negative));
+    assert_eq!(OmegaUInt::<u32>::try_from(OmegaInt::<i64>::MOmega), Err(TryFromOmegaIntError::Negative));
+    assert_eq!(OmegaUInt::<u8>::try_from(OmegaInt::Integer(300i64)), Err(TryFromOmegaIntError::OutOfRange));
+    assert_eq!(OmegaInt::<i64>::from(Natural(9u32)), OmegaInt::Integer(9));
+    assert_eq!(OmegaInt::<i64>::from(OmegaUInt::<u32>::Omega), OmegaInt::POmega);
+    // The length of a range is nonnegative
+    let (start, end): (OmegaInt<i64>, OmegaInt<i64>) = (OmegaInt::Integer(-4), OmegaInt::Integer(6));
+    assert_eq!(OmegaUInt::<u64>::try_from(end - start), Ok(Natural(10)));
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut, Range};
+use std::rc::Rc;
+
+use super::generic_index::{Index, IndexMut};
+use super::ztensor_impls::{Elem, ZTensor, ZTensorElemRef};
+use super::ztensor_traits::*;
+
+/// A tensor with individually editable elements on top of a base tensor.
+///
+/// Elements that have been written are stored in an overlay and take
+/// precedence over the base tensor, whose value function computes all the
+/// other elements.
+#[derive(Clone)]
+pub struct OverlayZTensor<const N: usize, T = Elem> {
+    /// The tensor supplying the elements that were not written
+    base: ZTensor<N, T>,
+    /// Elements that were written, keyed by index
+    overrides: HashMap<[FiniteIndex; N], T>
+}
+
+impl<const N: usize, T> OverlayZTensor<N, T> {
+    /// Returns the number of elements that were written.
+    pub fn overrides_len(&self) -> usize {
+        self.overrides.len()
+    }
+
+    /// Discards the written elements and returns the base tensor.
+    pub fn into_base(self) -> ZTensor<N, T> {
+        self.base
+    }
+}
+
+impl<const N: usize, T: Clone + 'static> OverlayZTensor<N, T> {
+    /// Converts to a ZTensor computing the same elements.
+    ///
+    /// The written elements are copied, so later writes to this tensor do
+    /// not affect the result.
+    ///
+    /// # Returns
+    ///
+    /// A ZTensor with the same ranges and elements
+    pub fn to_ztensor(&self) -> ZTensor<N, T> {
+        let base = self.base.clone();
+        let overrides = Rc::new(self.overrides.clone());
+        ZTensor::from_ranges_values(&self.base.get_index_ranges(), move |idx: &[FiniteIndex; N]|{
+            match overrides.get(idx) {
+                Some(value) => value.clone(),
+                None => base.get_single_elem(idx)
+            }
+        })
+    }
+}
+
+/// Implementation of ZTensorLike trait for OverlayZTensor.
+///
+/// Written elements are served from the overlay, and all the others are
+/// computed by the base tensor.
+impl<const N: usize, T: Clone> ZTensorLike<N> for OverlayZTensor<N, T> {
+    type DType = T;
+
+    fn get_index_ranges(&self) -> [Range<OmegaIndex>; N] {
+        self.base.get_index_ranges()
+    }
+
+    fn get_single_elem(&self, indices: &[FiniteIndex; N]) -> Self::DType {
+        match self.overrides.get(indices) {
+            Some(value) => value.clone(),
+            None => self.base.get_single_elem(indices)
+        }
+    }
+}
+
+/// Implementation of Index trait for OverlayZTensor.
+impl<const N: usize, T: Clone> Index<[FiniteIndex; N]> for OverlayZTensor<N, T> {
+    type Output = T;
+    type DerefOutput<'a> = ZTensorElemRef<T> where T: 'a;
+
+    fn index<'a>(&'a self, index: [FiniteIndex; N]) -> Self::DerefOutput<'a> {
+        ZTensorElemRef {value: self.get_single_elem(&index)}
+    }
+}
+
+/// Mutable reference to an OverlayZTensor element.
+///
+/// The element can be modified through this wrapper, and is written into
+/// the overlay when the wrapper is dropped.
+pub struct OverlayElemMut<'a, const N: usize, T> {
+    /// The overlay receiving the element
+    overrides: &'a mut HashMap<[FiniteIndex; N], T>,
+    /// Index of the element
+    index: [FiniteIndex; N],
+    /// The current element value, only taken when dropped
+    value: Option<T>
+}
+
+impl<const N: usize, T> Deref for OverlayElemMut<'_, N, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.value.as_ref().expect("element is only taken when dropped")
+    }
+}
+
+impl<const N: usize, T> DerefMut for OverlayElemMut<'_, N, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.value.as_mut().expect("element is only taken when dropped")
+    }
+}
+
+impl<const N: usize, T> Drop for OverlayElemMut<'_, N, T> {
+    fn drop(&mut self) {
+        if let Some(value) = self.value.take() {
+            self.overrides.insert(self.index, value);
+        }
+    }
+}
+
+/// Implementation of IndexMut trait for OverlayZTensor.
+///
+/// Panics if the index is outside the ranges of the tensor.
+impl<const N: usize, T: Clone> IndexMut<[FiniteIndex; N]> for OverlayZTensor<N, T> {
+    type DerefMutOutput<'a> = OverlayElemMut<'a, N, T> where T: 'a;
+
+    fn index_mut<'a>(&'a mut self, index: [FiniteIndex; N]) -> Self::DerefMutOutput<'a> {
+        let ranges = self.base.get_index_ranges();
+        for (axis, (r, &i)) in ranges.iter().zip(index.iter()).enumerate() {
+            assert!(omega_range_contains(r, i), "Index {} is out of range in axis {}", i, axis);
+        }
+        let value = self.get_single_elem(&index);
+        OverlayElemMut {overrides: &mut self.overrides, index, value: Some(value)}
+    }
+}
+
+impl<const N: usize, T> ZTensor<N, T> {
+    /// Wraps this tensor in an overlay of individually written elements.
+    ///
+    /// # Returns
+    ///
+    /// An OverlayZTensor with no written elements yet
+    pub fn overlay(self) -> OverlayZTensor<N, T> {
+        OverlayZTensor {base: self, overrides: HashMap::new()}
+    }
+}
+
+#[test]
+fn test_overlay_index_mut(){
+    use super::omega_int::OmegaInt::*;
+    use super::ztensor_impls::ZMatrix;
+    let t = ZMatrix::from_ranges_values(&[Integer(0)..Integer(3), MOmega..POmega], |&[i, j]| Elem::new(i as f32, j as f32));
+    let mut o = t.overlay();
+    let x = Elem::new(-7.0, 0.5);
+    *o.index_mut([1, 2]) = x;
+    *o.index_mut([2, -100]) += Elem::new(1.0, 0.0);
+    assert_eq!(*o.index([1, 2]), x);
+    assert_eq!(o.get_single_elem(&[2, -100]), Elem::new(3.0, -100.0));
+    assert_eq!(*o.index([1, 3]), Elem::new(1.0, 3.0));
+    assert_eq!(o.get_single_elem(&[0, 2]), Elem::new(0.0, 2.0));
+    assert_eq!(o.overrides_len(), 2);
+
+    let snapshot = o.to_ztensor();
+    *o.index_mut([1, 2]) = Elem::new(0.0, 0.0);
+    assert_eq!(snapshot.get_single_elem(&[1, 2]), x);
+    assert_eq!(snapshot.get_index_ranges(), [Integer(0)..Integer(3), MOmega..POmega]);
+    assert_eq!(o.into_base().get_single_elem(&[1, 2]), Elem::new(1.0, 2.0));
+}
+
+#[test]
+#[should_panic(expected = "Index 3 is out of range in axis 0")]
+fn test_overlay_index_mut_out_of_range(){
+    use super::omega_int::OmegaInt::*;
+    use super::ztensor_impls::ZMatrix;
+    let mut o = ZMatrix::zeros(&[Integer(0)..Integer(3), MOmega..POmega]).overlay();
+    *o.index_mut([3, 0]) = Elem::new(1.0, 0.0);
+}
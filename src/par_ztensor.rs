@@ -0,0 +1,118 @@
+use std::ops::Range;
+
+use rayon::prelude::*;
+
+use super::ztensor_impls::{finite_ranges, Elem, ZTensor};
+use super::ztensor_traits::*;
+
+/// Converts a row-major offset into a multi-index.
+///
+/// # Arguments
+///
+/// * `offset` - Position in the row-major order, less than the product of `shape`
+/// * `starts` - First index of each dimension
+/// * `shape` - Length of each dimension
+///
+/// # Returns
+///
+/// The multi-index at the given position
+fn row_major_unravel<const N: usize>(mut offset: usize, starts: &[FiniteIndex; N], shape: &[usize; N]) -> [FiniteIndex; N] {
+    let mut index = *starts;
+    for axis in (0..N).rev() {
+        index[axis] += (offset % shape[axis]) as FiniteIndex;
+        offset /= shape[axis];
+    }
+    index
+}
+
+/// A tensor whose value function can be evaluated from several threads.
+///
+/// ParZTensor mirrors ZTensor, but its value function is required to be
+/// `Send + Sync` so that finite tensors can be materialized in parallel.
+#[derive(Clone)]
+pub struct ParZTensor<const N: usize> {
+    /// The index ranges for each dimension
+    index_ranges: [Range<OmegaIndex>; N],
+    /// Thread-safe function that computes the tensor elements given indices
+    value_getter: Box<dyn SyncCloneableFn<N, Elem>>
+}
+
+/// Implementation of ZTensorLike trait for ParZTensor.
+impl<const N: usize> ZTensorLike<N> for ParZTensor<N> {
+    type DType = Elem;
+
+    fn get_index_ranges(&self) -> [Range<OmegaIndex>; N] {
+        self.index_ranges.clone()
+    }
+
+    fn get_single_elem(&self, indices: &[FiniteIndex; N]) -> Self::DType {
+        (self.value_getter)(indices)
+    }
+}
+
+impl<const N: usize> ParZTensor<N> {
+    /// Creates a new ParZTensor with specified ranges and a thread-safe value function.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - Array of ranges for each dimension
+    /// * `value_getter` - Function that computes the tensor element for given indices
+    pub fn from_ranges_values<F: SyncCloneableFn<N, Elem> + 'static>(ranges: &[Range<OmegaIndex>; N], value_getter: F) -> Self {
+        Self {index_ranges: ranges.clone(), value_getter: Box::new(value_getter)}
+    }
+
+    /// Materializes a finite tensor in row-major order, computing the elements in parallel.
+    ///
+    /// Panics if any dimension is infinite.
+    ///
+    /// # Returns
+    ///
+    /// The elements in row-major order, together with the length of each dimension
+    pub fn to_vec_par(&self) -> (Vec<Elem>, [usize; N]) {
+        let ranges = finite_ranges(&self.index_ranges);
+        let shape = ranges.clone().map(|r| (r.end - r.start).max(0) as usize);
+        let starts = ranges.map(|r| r.start);
+        let total: usize = shape.iter().product();
+        let value_getter = &self.value_getter;
+        let data = (0..total).into_par_iter().map(|offset| value_getter(&row_major_unravel(offset, &starts, &shape))).collect();
+        (data, shape)
+    }
+
+    /// Converts to a ZTensor sharing the same ranges and value function.
+    ///
+    /// # Returns
+    ///
+    /// A ZTensor computing the same elements, without the thread-safety requirement
+    pub fn to_ztensor(&self) -> ZTensor<N> {
+        let value_getter = self.value_getter.clone();
+        ZTensor::from_ranges_values(&self.index_ranges, move |idx: &[FiniteIndex; N]| value_getter(idx))
+    }
+}
+
+#[test]
+fn test_to_vec_par_matches_to_vec(){
+    use super::omega_int::OmegaInt::*;
+    let t = ParZTensor::from_ranges_values(&[Integer(-5)..Integer(40), Integer(3)..Integer(70)], |&[i, j]: &[FiniteIndex; 2]| {
+        Elem::new((i * j) as f32, (i - j) as f32).powf(0.5)
+    });
+    let (par, par_shape) = t.to_vec_par();
+    let (serial, serial_shape) = t.to_ztensor().to_vec();
+    assert_eq!(par_shape, [45, 67]);
+    assert_eq!(par_shape, serial_shape);
+    assert_eq!(par, serial);
+}
+
+#[test]
+fn test_to_vec_par_row_major_order(){
+    use super::omega_int::OmegaInt::*;
+    let t = ParZTensor::from_ranges_values(&[Integer(1)..Integer(3), Integer(-1)..Integer(2), Integer(0)..Integer(4)], |&[a, b, c]: &[FiniteIndex; 3]| {
+        Elem::new((100 * a + 10 * b + c) as f32, 0.0)
+    });
+    let (data, shape) = t.to_vec_par();
+    assert_eq!(shape, [2, 3, 4]);
+    assert_eq!(data, t.to_ztensor().to_vec().0);
+    assert_eq!(data[0], Elem::new(90.0, 0.0));
+    assert_eq!(data[23], Elem::new(213.0, 0.0));
+    let empty = ParZTensor::from_ranges_values(&[Integer(0)..Integer(3), Integer(2)..Integer(2)], |_: &[FiniteIndex; 2]| Elem::new(1.0, 0.0));
+    assert_eq!(empty.to_vec_par(), (vec![], [3, 0]));
+}
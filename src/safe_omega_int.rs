@@ -0,0 +1,121 @@
+//! This module defines SafeOmegaInt, a wrapper around OmegaInt whose arithmetic operators never panic.
+//! Overflowing finite results saturate to POmega or MOmega, and indeterminate forms such as
+//! `POmega + MOmega` or `0 * POmega` resolve to zero.
+
+use std::ops::{Add, Div, Mul, Sub};
+
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Zero};
+
+use super::omega_int::*;
+
+/// An OmegaInt with total, non-panicking arithmetic.
+///
+/// The operators use the checked forms of the OmegaInt operations and
+/// resolve every failure to a documented value:
+///
+/// * Finite overflow saturates to POmega or MOmega following the sign of the exact result.
+/// * `POmega + MOmega`, `POmega - POmega` and `MOmega - MOmega` are zero.
+/// * A zero times an infinity is zero.
+/// * A nonzero value divided by zero is the infinity with the sign of the dividend, and `0 / 0` is zero.
+/// * An infinity divided by an infinity is zero.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct SafeOmegaInt<N>(pub OmegaInt<N>);
+
+impl<N> SafeOmegaInt<N> {
+    /// Returns the wrapped OmegaInt.
+    pub fn into_inner(self) -> OmegaInt<N> {
+        self.0
+    }
+}
+
+impl<N> From<OmegaInt<N>> for SafeOmegaInt<N> {
+    fn from(value: OmegaInt<N>) -> Self {
+        SafeOmegaInt(value)
+    }
+}
+
+/// Returns the infinity with the given sign, or zero for a zero sign.
+fn saturate<N: Zero>(sign: Sign) -> OmegaInt<N> {
+    match sign {
+        1 => POmega,
+        -1 => MOmega,
+        _ => Integer(N::zero())
+    }
+}
+
+impl<N: CheckedAdd + PrimGetSign + Zero> Add for SafeOmegaInt<N> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        let (sl, sr) = (self.0.get_sign(), rhs.0.get_sign());
+        match self.0.checked_add(&rhs.0) {
+            Some(x) => SafeOmegaInt(x),
+            // Operands of equal sign overflowed, otherwise this is POmega + MOmega
+            None if sl == sr => SafeOmegaInt(saturate(sl)),
+            None => SafeOmegaInt(Integer(N::zero()))
+        }
+    }
+}
+
+impl<N: CheckedSub + PrimGetSign + Zero> Sub for SafeOmegaInt<N> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        let (sl, sr) = (self.0.get_sign(), rhs.0.get_sign());
+        match self.0.checked_sub(&rhs.0) {
+            Some(x) => SafeOmegaInt(x),
+            // Subtracting an infinity from itself
+            None if sl == sr => SafeOmegaInt(Integer(N::zero())),
+            None if sl != 0 => SafeOmegaInt(saturate(sl)),
+            None => SafeOmegaInt(saturate(-sr))
+        }
+    }
+}
+
+impl<N: CheckedMul + PrimGetSign + Zero> Mul for SafeOmegaInt<N> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let (sl, sr) = (self.0.get_sign(), rhs.0.get_sign());
+        match self.0.checked_mul(&rhs.0) {
+            Some(x) => SafeOmegaInt(x),
+            // Either a finite overflow, or zero times an infinity when the sign product is zero
+            None => SafeOmegaInt(saturate(sl * sr))
+        }
+    }
+}
+
+impl<N: CheckedDiv + CheckedAdd + PrimGetSign + Zero> Div for SafeOmegaInt<N> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        let (sl, sr) = (self.0.get_sign(), rhs.0.get_sign());
+        match self.0.checked_div(&rhs.0) {
+            Some(x) => SafeOmegaInt(x),
+            None if sr == 0 => SafeOmegaInt(saturate(sl)),
+            None if self.0.is_pmomega() != 0 && rhs.0.is_pmomega() != 0 => SafeOmegaInt(Integer(N::zero())),
+            None => SafeOmegaInt(saturate(sl * sr))
+        }
+    }
+}
+
+#[test]
+fn test_safe_omega_int(){
+    let s = |x: OmegaInt<i8>| SafeOmegaInt(x);
+    // Ordinary arithmetic is unchanged
+    assert_eq!(s(Integer(5)) + s(Integer(-7)), s(Integer(-2)));
+    assert_eq!(s(Integer(6)) / s(Integer(-4)), s(Integer(-1)));
+    // Finite overflow saturates
+    assert_eq!(s(Integer(100)) + s(Integer(100)), s(POmega));
+    assert_eq!(s(Integer(-100)) + s(Integer(-100)), s(MOmega));
+    assert_eq!(s(Integer(-100)) - s(Integer(100)), s(MOmega));
+    assert_eq!(s(Integer(0)) - s(Integer(-128)), s(POmega));
+    assert_eq!(s(Integer(-20)) * s(Integer(-20)), s(POmega));
+    assert_eq!(s(Integer(20)) * s(Integer(-20)), s(MOmega));
+    assert_eq!(s(Integer(-128)) / s(Integer(-1)), s(POmega));
+    // Indeterminate forms resolve to zero
+    assert_eq!(s(POmega) + s(MOmega), s(Integer(0)));
+    assert_eq!(s(POmega) - s(POmega), s(Integer(0)));
+    assert_eq!(s(Integer(0)) * s(MOmega), s(Integer(0)));
+    assert_eq!(s(POmega) / s(MOmega), s(Integer(0)));
+    assert_eq!(s(Integer(0)) / s(Integer(0)), s(Integer(0)));
+    // Division by zero gives the infinity with the sign of the dividend
+    assert_eq!(s(Integer(-3)) / s(Integer(0)), s(MOmega));
+    assert_eq!(s(POmega) / s(Integer(0)), s(POmega));
+}
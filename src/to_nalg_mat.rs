@@ -1,10 +1,9 @@
 use std::fmt::Debug;
-use std::ops::Range;
+use std::rc::Rc;
 
-use super::omega_int::OmegaInt;
-use super::ztensor_impls::{Elem, ZMatrix};
+use super::ztensor_impls::{BackingKind, Elem, ZMatrix, ZVector};
 use super::ztensor_traits::*;
-use nalgebra::DMatrix;
+use nalgebra::{DMatrix, DVector};
 
 /// Trait for converting ZTensor objects to nalgebra matrices.
 ///
@@ -18,38 +17,83 @@ pub trait ToNAlgMat {
     ///
     /// This method extracts the elements from a two-dimensional ZTensor
     /// and creates a corresponding nalgebra matrix with the same elements.
-    fn to_nalg_mat(&self) -> DMatrix<Self::Elem>  where Self::Elem: 'static;
+    /// Panics if the tensor has an infinite dimension.
+    fn to_nalg_mat(&self) -> DMatrix<Self::Elem>  where Self::Elem: 'static {
+        match self.try_to_nalg_mat() {
+            Some(mat) => mat,
+            None => panic!("Cannot convert a tensor with infinite or negative-length dimensions to a nalgebra matrix")
+        }
+    }
+
+    /// Converts the tensor to a nalgebra DMatrix, if its dimensions are finite.
+    ///
+    /// # Returns
+    ///
+    /// The converted matrix, or None if any dimension is infinite or has negative length
+    fn try_to_nalg_mat(&self) -> Option<DMatrix<Self::Elem>>  where Self::Elem: 'static {
+        self.try_to_nalg_mat_with_progress(|_, _|{})
+    }
+
+    /// Converts the tensor to a nalgebra DMatrix while reporting progress.
+    ///
+    /// This behaves like `to_nalg_mat`, and additionally invokes `progress`
+    /// with the number of completed elements and the total number of
+    /// elements. It is called about a hundred times over the conversion, and
+    /// always a final time with `completed == total`.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - Callback receiving `(completed, total)` element counts
+    fn to_nalg_mat_with_progress(&self, progress: impl FnMut(usize, usize)) -> DMatrix<Self::Elem>  where Self::Elem: 'static {
+        match self.try_to_nalg_mat_with_progress(progress) {
+            Some(mat) => mat,
+            None => panic!("Cannot convert a tensor with infinite or negative-length dimensions to a nalgebra matrix")
+        }
+    }
+
+    /// Converts the tensor to a nalgebra DMatrix while reporting progress, if its dimensions are finite.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - Callback receiving `(completed, total)` element counts
+    ///
+    /// # Returns
+    ///
+    /// The converted matrix, or None if any dimension is infinite or has negative length
+    fn try_to_nalg_mat_with_progress(&self, progress: impl FnMut(usize, usize)) -> Option<DMatrix<Self::Elem>>  where Self::Elem: 'static;
 }
 
 /// Implementation of ToNAlgMat for any 2D ZTensorLike type.
 ///
 /// This allows any 2D tensor-like object to be converted to a nalgebra matrix
-/// as long as its element type can be cloned and compared.
-impl<T> ToNAlgMat for T where T: ZTensorLike<2>, T::DType: Clone + PartialEq + Debug{
+/// as long as its element type can be cloned and compared. Unsized types are
+/// included, so the conversion also works through `dyn ZTensorLike<2>` trait objects.
+impl<T> ToNAlgMat for T where T: ZTensorLike<2> + ?Sized, T::DType: Clone + PartialEq + Debug{
     type Elem = T::DType;
-    fn to_nalg_mat(&self) -> DMatrix<Self::Elem> where T::DType: 'static {
-        let ranges = self.get_index_ranges();
-        let ranges_len = ranges.clone().map(|r: Range<OmegaIndex>|{r.end - r.start});
-        // Check the length of ranges are all finite
-        let finite_len = ranges_len.map(|l|{
-            match l {
-                OmegaInt::Integer(x) => {
-                    if x < 0 {panic!()}
-                    x as usize
-                },
-                _ => panic!()
-            }
-        });
-        let start_indices = ranges.map(|r|{
-            match r.start {
-                OmegaInt::Integer(x) => x,
-                _ => panic!()
-            }
-        });
+    fn try_to_nalg_mat_with_progress(&self, mut progress: impl FnMut(usize, usize)) -> Option<DMatrix<Self::Elem>> where T::DType: 'static {
+        if !self.all_dims_finite() {
+            return None;
+        }
+        let [rows, cols] = self.get_index_ranges();
+        let ranges = [rows.start.to_finite()?..rows.end.to_finite()?, cols.start.to_finite()?..cols.end.to_finite()?];
+        if ranges.iter().any(|r| r.end < r.start) {
+            return None;
+        }
+        let finite_len = ranges.clone().map(|r| (r.end - r.start) as usize);
+        let start_indices = ranges.map(|r| r.start);
+        let total = finite_len[0] * finite_len[1];
+        let report_every = (total / 100).max(1);
+        let mut completed = 0;
         let mat: DMatrix<Self::Elem> = DMatrix::from_fn(finite_len[0], finite_len[1], |i: usize, j: usize|{
-            self.get_single_elem(&[start_indices[0]+i as FiniteIndex,start_indices[1]+j as FiniteIndex])
+            let value = self.get_single_elem(&[start_indices[0]+i as FiniteIndex,start_indices[1]+j as FiniteIndex]);
+            completed += 1;
+            if completed % report_every == 0 && completed != total {
+                progress(completed, total);
+            }
+            value
         });
-        mat
+        progress(total, total);
+        Some(mat)
     }
 }
 
@@ -58,7 +102,7 @@ mod test {
     use num_complex::Complex;
     use num_traits::ToPrimitive;
 
-    use super::{super::ztensor_impls::*, OmegaIndex, ToNAlgMat, ZTensorLikeFromRangesValues, ZTensorLikeSlice};
+    use super::{super::ztensor_impls::*, OmegaIndex, ToNAlgMat, ZTensorLike, ZTensorLikeFromRangesValues, ZTensorLikeSlice};
 
     #[test]
     fn test_ztensor_to_nalgebra_matrix(){
@@ -69,6 +113,133 @@ mod test {
         let m = trunc.to_nalg_mat();
         assert_eq!(m[(2,3)], Complex::<f32>::new(-2.to_f32().unwrap(), -2.to_f32().unwrap()))
     }
+
+    #[test]
+    fn test_trait_object_to_nalgebra_matrix(){
+        let t: ZMatrix = ZMatrix::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(2), OmegaIndex::Integer(1)..OmegaIndex::Integer(4)], |[i1, i2]|{
+            Complex::<f32>::new(i1.to_f32().unwrap(), i2.to_f32().unwrap())
+        });
+        let infinite: ZMatrix = ZMatrix::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::POmega, OmegaIndex::Integer(0)..OmegaIndex::Integer(2)], |_|{
+            Complex::<f32>::new(0.0, 0.0)
+        });
+        let sources: Vec<Box<dyn ZTensorLike<2, DType = Complex<f32>>>> = vec![Box::new(t.clone()), Box::new(t.memoized()), Box::new(infinite)];
+        let m = sources[0].to_nalg_mat();
+        assert_eq!(m.shape(), (2, 3));
+        assert_eq!(m[(1, 2)], Complex::<f32>::new(1.0, 3.0));
+        assert_eq!(sources[1].try_to_nalg_mat(), Some(m));
+        assert_eq!(sources[2].try_to_nalg_mat(), None);
+    }
+
+    #[test]
+    fn test_commutator_matches_nalgebra(){
+        let ranges = [OmegaIndex::Integer(0)..OmegaIndex::Integer(3), OmegaIndex::Integer(0)..OmegaIndex::Integer(3)];
+        let a: ZMatrix = ZMatrix::from_ranges_values(&ranges, |[i1, i2]|{
+            Complex::<f32>::new((i1 + 2 * i2).to_f32().unwrap(), (i1 - i2).to_f32().unwrap())
+        });
+        let b: ZMatrix = ZMatrix::from_ranges_values(&ranges, |[i1, i2]|{
+            Complex::<f32>::new((i1 * i2).to_f32().unwrap(), 1.0)
+        });
+        let (ma, mb) = (a.to_nalg_mat(), b.to_nalg_mat());
+        let expected = &ma * &mb - &mb * &ma;
+        let c = a.commutator(&b).to_nalg_mat();
+        assert!((c - expected).norm() < 1e-4);
+    }
+
+    #[test]
+    fn test_add_matches_nalgebra(){
+        let ranges = [OmegaIndex::Integer(0)..OmegaIndex::Integer(3), OmegaIndex::Integer(0)..OmegaIndex::Integer(4)];
+        let a: ZMatrix = ZMatrix::from_ranges_values(&ranges, |[i1, i2]|{
+            Complex::<f32>::new((i1 + 2 * i2).to_f32().unwrap(), (i1 - i2).to_f32().unwrap())
+        });
+        let b: ZMatrix = ZMatrix::from_ranges_values(&ranges, |[i1, i2]|{
+            Complex::<f32>::new((i1 * i2).to_f32().unwrap(), 1.0)
+        });
+        let expected = a.to_nalg_mat() + b.to_nalg_mat();
+        assert_eq!((&a + &b).to_nalg_mat(), expected);
+    }
+
+    #[test]
+    fn test_hadamard_sub_match_nalgebra(){
+        let a: ZMatrix = ZMatrix::from_ranges_values(&[OmegaIndex::MOmega..OmegaIndex::Integer(2), OmegaIndex::Integer(0)..OmegaIndex::POmega], |[i1, i2]|{
+            Complex::<f32>::new((i1 + 2 * i2).to_f32().unwrap(), (i1 - i2).to_f32().unwrap())
+        });
+        let b: ZMatrix = ZMatrix::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::POmega, OmegaIndex::MOmega..OmegaIndex::Integer(2)], |[i1, i2]|{
+            Complex::<f32>::new((i1 * i2).to_f32().unwrap() + 0.5, 1.0)
+        });
+        let region = [OmegaIndex::Integer(0)..OmegaIndex::Integer(2), OmegaIndex::Integer(0)..OmegaIndex::Integer(2)];
+        let (ma, mb) = (a.get_slice(&region).to_nalg_mat(), b.get_slice(&region).to_nalg_mat());
+        let product = a.hadamard(&b);
+        let difference = &a - &b;
+        assert_eq!(product.get_index_ranges(), region);
+        assert_eq!(difference.get_index_ranges(), region);
+        assert_eq!(product.to_nalg_mat(), ma.component_mul(&mb));
+        assert_eq!(difference.to_nalg_mat(), ma - mb);
+    }
+
+    #[test]
+    fn test_matmul_matches_nalgebra(){
+        let a: ZMatrix = ZMatrix::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(2), OmegaIndex::Integer(-1)..OmegaIndex::Integer(2)], |[i1, i2]|{
+            Complex::<f32>::new((i1 + 2 * i2).to_f32().unwrap(), (i1 - i2).to_f32().unwrap())
+        });
+        let b: ZMatrix = ZMatrix::from_ranges_values(&[OmegaIndex::Integer(-1)..OmegaIndex::Integer(2), OmegaIndex::Integer(3)..OmegaIndex::Integer(5)], |[i1, i2]|{
+            Complex::<f32>::new((i1 * i2).to_f32().unwrap(), 1.0)
+        });
+        let expected = a.to_nalg_mat() * b.to_nalg_mat();
+        let c = a.matmul(&b).to_nalg_mat();
+        assert_eq!(c.shape(), (2, 2));
+        assert!((c - expected).norm() < 1e-4);
+    }
+
+    #[test]
+    fn test_matvec_matches_nalgebra(){
+        use nalgebra::DVector;
+        let a: ZMatrix = ZMatrix::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(3), OmegaIndex::Integer(2)..OmegaIndex::Integer(6)], |[i1, i2]|{
+            Complex::<f32>::new((i1 + 2 * i2).to_f32().unwrap(), (i1 - i2).to_f32().unwrap())
+        });
+        let v: ZVector = ZVector::from_ranges_values(&[OmegaIndex::Integer(2)..OmegaIndex::Integer(6)], |[i]|{
+            Complex::<f32>::new(1.0, i.to_f32().unwrap())
+        });
+        let mv = DVector::from_fn(4, |i, _| Complex::<f32>::new(1.0, (i + 2) as f32));
+        let expected = a.to_nalg_mat() * mv;
+        let product = a.matvec(&v);
+        for i in 0..3 {
+            assert!((product.get_single_elem(&[i as i64]) - expected[i]).norm() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_sum_rows_cols_match_nalgebra(){
+        let t: ZMatrix = ZMatrix::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(3), OmegaIndex::Integer(0)..OmegaIndex::Integer(4)], |[i1, i2]|{
+            Complex::<f32>::new((i1 + 2 * i2).to_f32().unwrap(), (i1 * i2).to_f32().unwrap())
+        });
+        let m = t.to_nalg_mat();
+        let (row_sum, column_sum) = (m.row_sum(), m.column_sum());
+        let (sum_rows, sum_cols) = (t.sum_rows(), t.sum_cols());
+        assert_eq!(sum_rows.get_index_ranges(), [OmegaIndex::Integer(0)..OmegaIndex::Integer(4)]);
+        assert_eq!(sum_cols.get_index_ranges(), [OmegaIndex::Integer(0)..OmegaIndex::Integer(3)]);
+        for j in 0..4 {
+            assert_eq!(sum_rows.get_single_elem(&[j as i64]), row_sum[j]);
+        }
+        for i in 0..3 {
+            assert_eq!(sum_cols.get_single_elem(&[i as i64]), column_sum[i]);
+        }
+    }
+
+    #[test]
+    fn test_companion_matrix_eigenvalues(){
+        // x^2 - 3x + 2 = (x - 1)(x - 2)
+        let coeffs = ZVector::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(2)], |&[i]|{
+            Complex::<f32>::new([2.0, -3.0][i as usize], 0.0)
+        });
+        let c = ZMatrix::companion_matrix(&coeffs).to_nalg_mat();
+        let mut roots: Vec<f32> = c.schur().eigenvalues().unwrap().iter().map(|x| {
+            assert!(x.im.abs() < 1e-4);
+            x.re
+        }).collect();
+        roots.sort_by(f32::total_cmp);
+        assert!((roots[0] - 1.0).abs() < 1e-4);
+        assert!((roots[1] - 2.0).abs() < 1e-4);
+    }
 }
 
 /// Converts a nalgebra DMatrix to a ZMatrix.
@@ -87,7 +258,26 @@ mod test {
 pub fn nalgebra_mat_to_zmat(mat: DMatrix<Elem>) -> ZMatrix {
     ZMatrix::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(mat.nrows() as FiniteIndex), OmegaIndex::Integer(0)..OmegaIndex::Integer(mat.ncols() as FiniteIndex)], move |[i1, i2]|{
         mat[(*i1 as usize,*i2 as usize)]
-    })
+    }).with_backing(BackingKind::Buffer)
+}
+
+/// Creates a ZMatrix view over a shared nalgebra DMatrix.
+///
+/// Unlike `nalgebra_mat_to_zmat`, the matrix is shared through an `Rc`, so
+/// cloning the resulting ZMatrix or deriving tensors from it only clones the
+/// handle rather than the matrix buffer. The ranges start from 0.
+///
+/// # Arguments
+///
+/// * `mat` - The shared nalgebra DMatrix to view
+///
+/// # Returns
+///
+/// A ZMatrix reading its elements from `mat`
+pub fn zmat_view(mat: Rc<DMatrix<Elem>>) -> ZMatrix {
+    ZMatrix::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(mat.nrows() as FiniteIndex), OmegaIndex::Integer(0)..OmegaIndex::Integer(mat.ncols() as FiniteIndex)], move |[i1, i2]|{
+        mat[(*i1 as usize,*i2 as usize)]
+    }).with_backing(BackingKind::Buffer)
 }
 
 #[test]
@@ -97,3 +287,148 @@ fn test_nalgebra_mat_to_zmat(){
     let mat2 = zmat.to_nalg_mat();
     assert_eq!(mat, mat2);
 }
+
+#[test]
+fn test_zmat_view(){
+    let mat: Rc<DMatrix<Elem>> = Rc::new(DMatrix::from_row_slice(2,3, &([1., 2., 3., 4., 5., 6.].map(|x|{x.into()}))));
+    let view = zmat_view(mat.clone());
+    assert_eq!(Rc::strong_count(&mat), 2);
+    let view2 = view.clone();
+    let slice = view.get_slice(&[OmegaIndex::Integer(1)..OmegaIndex::Integer(2), OmegaIndex::Integer(0)..OmegaIndex::Integer(3)]);
+    // Clones share the same buffer instead of copying it
+    assert_eq!(Rc::strong_count(&mat), 4);
+    assert_eq!(view2.get_single_elem(&[0, 2]), 3.0.into());
+    assert_eq!(slice.get_single_elem(&[1, 1]), 5.0.into());
+    assert_eq!(view.to_nalg_mat(), *mat);
+    assert_eq!(view.backing(), BackingKind::Buffer);
+}
+
+#[test]
+fn test_to_nalg_mat_with_progress(){
+    let zmat = ZMatrix::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(30), OmegaIndex::Integer(0)..OmegaIndex::Integer(40)], |&[i, j]|{
+        Elem::new(i as f32, j as f32)
+    });
+    let mut calls: Vec<(usize, usize)> = Vec::new();
+    let mat = zmat.to_nalg_mat_with_progress(|completed, total|{calls.push((completed, total))});
+    assert_eq!(mat, zmat.to_nalg_mat());
+    assert_eq!(calls.len(), 100);
+    assert!(calls.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(calls.iter().all(|&(_, total)| total == 1200));
+    assert_eq!(calls.last(), Some(&(1200, 1200)));
+}
+
+impl ZMatrix {
+    /// Computes the operator norm, the largest singular value, of a finite matrix.
+    ///
+    /// The matrix is converted to a nalgebra DMatrix and its singular values
+    /// are computed. Panics if the matrix has an infinite dimension.
+    ///
+    /// # Returns
+    ///
+    /// The largest singular value, or 0 for an empty matrix
+    pub fn operator_norm(&self) -> f32 {
+        self.to_nalg_mat().singular_values().iter().fold(0.0, |acc: f32, &s| acc.max(s))
+    }
+}
+
+#[test]
+fn test_operator_norm(){
+    let diag = [Elem::new(1.0, 0.0), Elem::new(0.0, -3.0), Elem::new(-2.0, 0.0)];
+    let zmat = ZMatrix::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(3), OmegaIndex::Integer(0)..OmegaIndex::Integer(3)], move |&[i, j]|{
+        if i == j { diag[i as usize] } else { Elem::new(0.0, 0.0) }
+    });
+    assert!((zmat.operator_norm() - 3.0).abs() < 1e-5);
+}
+
+impl ZMatrix {
+    /// Checks whether a finite real matrix is symmetric positive definite.
+    ///
+    /// The matrix must be square, have imaginary parts within `tol` of zero
+    /// and be symmetric within `tol`. The real part is then converted to a
+    /// nalgebra DMatrix and a Cholesky decomposition is attempted.
+    /// Panics if the matrix has an infinite dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - Tolerance for the imaginary parts and the symmetry check
+    ///
+    /// # Returns
+    ///
+    /// True if the matrix is symmetric and the Cholesky decomposition succeeds
+    pub fn is_spd(&self, tol: f32) -> bool {
+        let mat = self.to_nalg_mat();
+        if !mat.is_square() {
+            return false;
+        }
+        let n = mat.nrows();
+        for i in 0..n {
+            for j in 0..n {
+                if mat[(i, j)].im.abs() > tol || (mat[(i, j)].re - mat[(j, i)].re).abs() > tol {
+                    return false;
+                }
+            }
+        }
+        mat.map(|x| x.re).cholesky().is_some()
+    }
+}
+
+#[test]
+fn test_is_spd(){
+    let spd = crate::zmatrix![[4, 1, 0], [1, 3, -1], [0, -1, 2]];
+    assert!(spd.is_spd(1e-6));
+    let indefinite = crate::zmatrix![[1, 2], [2, 1]];
+    assert!(!indefinite.is_spd(1e-6));
+    let nonsymmetric = crate::zmatrix![[2, 1], [0, 2]];
+    assert!(!nonsymmetric.is_spd(1e-6));
+}
+
+impl ZMatrix {
+    /// Solves the linear system `self * x = rhs` for a finite square matrix.
+    ///
+    /// Both operands are converted to nalgebra and the system is solved by
+    /// LU decomposition. The solution is indexed like the columns of the
+    /// matrix. Panics if the matrix has an infinite dimension, is not square,
+    /// or if the length of `rhs` differs from the number of rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The right hand side vector
+    ///
+    /// # Returns
+    ///
+    /// The solution vector, or None if the matrix is singular
+    pub fn solve(&self, rhs: &ZVector) -> Option<ZVector> {
+        let mat = self.to_nalg_mat();
+        assert!(mat.is_square(), "Cannot solve a linear system with a non-square matrix");
+        let [rhs_range] = rhs.get_index_ranges();
+        let rhs_start = match (rhs_range.start.to_finite(), rhs_range.end.to_finite()) {
+            (Some(start), Some(end)) if end - start == mat.nrows() as FiniteIndex => start,
+            _ => panic!("Right hand side must be finite with as many elements as the matrix has rows")
+        };
+        let b = DVector::from_fn(mat.nrows(), |i, _| rhs.get_single_elem(&[rhs_start + i as FiniteIndex]));
+        let x = Rc::new(mat.lu().solve(&b)?);
+        let col_range = self.get_index_ranges()[1].clone();
+        let col_start = col_range.start.to_finite().unwrap();
+        Some(ZVector::from_ranges_values(&[col_range], move |&[i]|{
+            x[(i - col_start) as usize]
+        }))
+    }
+}
+
+#[test]
+fn test_solve(){
+    let a = crate::zmatrix![[2, 1, 0], [1, 3, 1], [0, 1, 4]];
+    // The solution is (1, -2, i)
+    let b_values = [Elem::new(0.0, 0.0), Elem::new(-5.0, 1.0), Elem::new(-2.0, 4.0)];
+    let b = ZVector::from_ranges_values(&[OmegaIndex::Integer(5)..OmegaIndex::Integer(8)], move |&[i]| b_values[(i - 5) as usize]);
+    let x = a.solve(&b).unwrap();
+    assert_eq!(x.get_index_ranges(), [OmegaIndex::Integer(0)..OmegaIndex::Integer(3)]);
+    let expected = [Elem::new(1.0, 0.0), Elem::new(-2.0, 0.0), Elem::new(0.0, 1.0)];
+    for i in 0..3 {
+        assert!((x.get_single_elem(&[i]) - expected[i as usize]).norm() < 1e-5);
+    }
+
+    let singular = crate::zmatrix![[1, 2], [2, 4]];
+    let b = ZVector::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(2)], |_| Elem::new(1.0, 0.0));
+    assert!(singular.solve(&b).is_none());
+}
@@ -1,36 +1,61 @@
 use super::ztensor_traits::*;
 use super::generic_index::Index;
-use std::ops::{Deref, Range};
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, Deref, Mul, Range, Sub};
+use std::rc::Rc;
+use std::sync::Arc;
 use num_complex::Complex;
+use num_traits::{CheckedMul, One, Zero};
 
-/// Element type used in ZTensor implementations.
+/// Default element type used in ZTensor implementations.
 /// Uses complex numbers with 32-bit floating point components.
 pub type Elem = Complex<f32>;
 
+/// Describes how a ZTensor computes its elements.
+///
+/// This is metadata for introspection, for instance to decide whether
+/// materializing a tensor is worthwhile. It does not affect the values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackingKind {
+    /// Every element has the same value
+    Constant,
+    /// Elements are computed by an arbitrary value function
+    Closure,
+    /// Elements are computed once and then served from a cache
+    Memoized,
+    /// Elements are read from a materialized buffer
+    Buffer
+}
+
 /// A tensor implementation supporting dimensions indexed with omega integers.
 ///
 /// ZTensor is a generic N-dimensional tensor that can have potentially infinite
 /// ranges, using omega integers for indexing. The actual values are computed
-/// on-demand through a function.
+/// on-demand through a function. Elements are of type `T`, which defaults to
+/// the complex `Elem`.
 #[derive(Clone)]
-pub struct ZTensor<const N: usize> {
+pub struct ZTensor<const N: usize, T = Elem> {
     /// The index ranges for each dimension
     index_ranges: [Range<OmegaIndex>; N],
     /// Function that computes the tensor elements given indices
-    value_getter: Box<dyn CloneableFn<N, Elem>>
+    value_getter: Box<dyn CloneableFn<N, T>>,
+    /// How the value function computes the elements
+    backing: BackingKind
 }
 
 /// Reference to a ZTensor element.
 ///
 /// This wrapper provides dereferencing capabilities to access the underlying
-/// complex value.
-pub struct ZTensorElemRef {
+/// element value.
+pub struct ZTensorElemRef<T = Elem> {
     /// The actual element value
-    value: Elem
+    pub(crate) value: T
 }
 
-impl Deref for ZTensorElemRef {
-    type Target = Elem;
+impl<T> Deref for ZTensorElemRef<T> {
+    type Target = T;
     fn deref(&self) -> &Self::Target {
         return &self.value;
     }
@@ -39,8 +64,8 @@ impl Deref for ZTensorElemRef {
 /// Implementation of ZTensorLike trait for ZTensor.
 ///
 /// This provides the core functionality for accessing tensor elements and ranges.
-impl<const N: usize> ZTensorLike<N> for ZTensor<N> {
-    type DType = Elem;
+impl<const N: usize, T> ZTensorLike<N> for ZTensor<N, T> {
+    type DType = T;
     
     /// Returns the index ranges for all dimensions.
     fn get_index_ranges(&self) -> [Range<OmegaIndex>; N] {
@@ -54,7 +79,7 @@ impl<const N: usize> ZTensorLike<N> for ZTensor<N> {
 }
 
 /// Implementation for creating ZTensor from ranges and a value function.
-impl<const N: usize> ZTensorLikeFromRangesValues<N> for ZTensor<N> {
+impl<const N: usize, T> ZTensorLikeFromRangesValues<N> for ZTensor<N, T> {
     /// Creates a new ZTensor with specified ranges and a function to compute values.
     ///
     /// # Arguments
@@ -62,17 +87,17 @@ impl<const N: usize> ZTensorLikeFromRangesValues<N> for ZTensor<N> {
     /// * `ranges` - Array of ranges for each dimension
     /// * `value_getter` - Function that computes the tensor element for given indices
     fn from_ranges_values<F: CloneableFn<N, Self::DType> + 'static>(ranges: &[Range<OmegaIndex>; N], value_getter: F) -> Self {
-        let bo: Box<dyn CloneableFn<N, Elem>> = Box::new(value_getter);
-        Self {index_ranges: ranges.clone(), value_getter: bo}
+        let bo: Box<dyn CloneableFn<N, T>> = Box::new(value_getter);
+        Self {index_ranges: ranges.clone(), value_getter: bo, backing: BackingKind::Closure}
     }
 }
 
 /// Implementation of Index trait for ZTensor.
 ///
 /// Allows using array indexing syntax `(tensor[indices])` to access elements.
-impl<const N: usize> Index<[FiniteIndex; N]> for ZTensor<N> {
-    type Output = Elem;
-    type DerefOutput<'a> = ZTensorElemRef;
+impl<const N: usize, T> Index<[FiniteIndex; N]> for ZTensor<N, T> {
+    type Output = T;
+    type DerefOutput<'a> = ZTensorElemRef<T> where T: 'a;
     
     /// Returns a reference to the element at the specified indices.
     fn index<'a>(&'a self, index: [FiniteIndex; N]) -> Self::DerefOutput<'a> {
@@ -98,6 +123,27 @@ fn test_ztensor(){
     assert_eq!(*t2.index([1,2]), 21.0.into());
 }
 
+#[test]
+fn test_real_element_types(){
+    use super::omega_int::OmegaInt::*;
+    let m = ZTensor::<2, f64>::from_ranges_values(&[Integer(0)..Integer(2), Integer(0)..Integer(3)], |&[i, j]| i as f64 + 0.5 * j as f64);
+    assert_eq!(m.get_single_elem(&[1, 2]), 2.0);
+    assert_eq!(*m.index([1, 1]), 1.5);
+    assert_eq!(m.conj_trans().get_single_elem(&[2, 1]), 2.0);
+    assert_eq!(m.sum(), 6.0);
+    assert_eq!((&m + &m).get_single_elem(&[0, 1]), 1.0);
+    assert_eq!(m.map(|x| Elem::new(0.0, x as f32)).get_single_elem(&[1, 0]), Elem::new(0.0, 1.0));
+    assert_eq!(m.get_slice(&[Integer(1)..Integer(2), Integer(0)..Integer(3)]).to_vec(), (vec![1.0, 1.5, 2.0], [1, 3]));
+
+    let v = ZTensor::<1, i32>::from_flat(vec![3, -1, 4], [3]);
+    assert_eq!(v.get_single_elem(&[2]), 4);
+    assert_eq!(v.sum(), 6);
+    assert_eq!(v.product(), -12);
+    assert_eq!(v.hadamard(&v).to_vec(), (vec![9, 1, 16], [3]));
+    assert_eq!((v.clone() - ZTensor::<1, i32>::zeros(&[MOmega..POmega])).to_vec(), v.to_vec());
+    assert_eq!(v.map(|x| x as f32).to_complex().get_single_elem(&[1]), Elem::new(-1.0, 0.0));
+}
+
 /// Type alias for a 0-dimensional ZTensor (scalar).
 pub type ZScalar = ZTensor<0>;
 
@@ -107,7 +153,7 @@ pub type ZVector = ZTensor<1>;
 /// Type alias for a 2-dimensional ZTensor (matrix).
 pub type ZMatrix = ZTensor<2>;
 
-impl ZMatrix {
+impl<T: Conjugate + 'static> ZTensor<2, T> {
     /// Returns the conjugate transpose of this matrix.
     ///
     /// This method creates a new matrix by swapping dimensions and taking
@@ -117,10 +163,2844 @@ impl ZMatrix {
     ///
     /// A new ZMatrix representing the conjugate transpose
     pub fn conj_trans(&self) -> Self {
+        self.permute_axes([1, 0]).conj()
+    }
+}
+
+impl<T: Zero + One + Clone + 'static> ZTensor<2, T> {
+    /// Creates the identity matrix of a given size.
+    ///
+    /// Element `[i, j]` is 1 when `i == j` and 0 otherwise, over the finite
+    /// range `0..n` in both dimensions.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Number of rows and columns
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated identity matrix
+    pub fn identity(n: usize) -> Self {
+        let range = OmegaIndex::Integer(0)..OmegaIndex::Integer(n as FiniteIndex);
+        Self::from_ranges_values(&[range.clone(), range], |&[i, j]: &[FiniteIndex; 2]|{
+            if i == j { T::one() } else { T::zero() }
+        })
+    }
+}
+
+#[test]
+fn test_identity(){
+    use super::omega_int::OmegaInt::*;
+    let id = ZMatrix::identity(4);
+    assert_eq!(id.get_index_ranges(), [Integer(0)..Integer(4), Integer(0)..Integer(4)]);
+    for i in 0..4 {
+        assert_eq!(id.get_single_elem(&[i, i]), Elem::one());
+        for j in (0..4).filter(|&j| j != i) {
+            assert_eq!(id.get_single_elem(&[i, j]), Elem::zero());
+        }
+    }
+    assert_eq!(ZTensor::<2, i32>::identity(3).sum(), 3);
+}
+
+impl ZMatrix {
+    /// Creates the permutation matrix of an index mapping.
+    ///
+    /// Element `[i, j]` is 1 when `j == perm[i]` and 0 otherwise, over the
+    /// finite range `0..len` in both dimensions. Multiplying a vector `x`
+    /// by this matrix gives the vector with elements `x[perm[i]]`.
+    /// Panics if `perm` is not a permutation of `0..len`.
+    ///
+    /// # Arguments
+    ///
+    /// * `perm` - The image of each row index
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated permutation ZMatrix
+    pub fn permutation_matrix(perm: &[usize]) -> ZMatrix {
+        let mut seen = vec![false; perm.len()];
+        for &p in perm {
+            assert!(p < perm.len() && !seen[p], "permutation_matrix requires a permutation of 0..{}", perm.len());
+            seen[p] = true;
+        }
+        let perm: Rc<Vec<usize>> = Rc::new(perm.to_vec());
+        let range = OmegaIndex::Integer(0)..OmegaIndex::Integer(perm.len() as FiniteIndex);
+        ZMatrix::from_ranges_values(&[range.clone(), range], move |&[i, j]|{
+            if perm[i as usize] == j as usize { Elem::one() } else { Elem::zero() }
+        })
+    }
+
+    /// Creates the unitary discrete Fourier transform matrix.
+    ///
+    /// Element `[j, k]` is `exp(-2 pi i j k / n) / sqrt(n)` over the finite
+    /// range `0..n` in both dimensions.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The size of the transform
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated DFT ZMatrix
+    pub fn dft_matrix(n: usize) -> ZMatrix {
+        let range = OmegaIndex::Integer(0)..OmegaIndex::Integer(n as FiniteIndex);
+        let n = n as FiniteIndex;
+        let scale = 1.0 / (n as f32).sqrt();
+        ZMatrix::from_ranges_values(&[range.clone(), range], move |&[j, k]|{
+            // Reduce the exponent first to keep the angle small and accurate
+            let angle = -2.0 * std::f32::consts::PI * ((j * k) % n) as f32 / n as f32;
+            Elem::from_polar(scale, angle)
+        })
+    }
+
+    /// Creates the companion matrix of a monic polynomial.
+    ///
+    /// For `x^n + c[n-1] x^(n-1) + ... + c[1] x + c[0]` the matrix is
+    /// `n x n` over `0..n`, with ones on the sub-diagonal and `-c[i]` in
+    /// row `i` of the last column. Its eigenvalues are the roots of the
+    /// polynomial. Panics if `coeffs` has an infinite range.
+    ///
+    /// # Arguments
+    ///
+    /// * `coeffs` - The coefficients below the leading one, starting from the constant term
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated companion ZMatrix
+    pub fn companion_matrix(coeffs: &ZVector) -> ZMatrix {
+        let [coeff_range] = coeffs.finite_index_ranges();
+        let (start, n) = (coeff_range.start, coeff_range.end - coeff_range.start);
+        let range = OmegaIndex::Integer(0)..OmegaIndex::Integer(n);
+        let valget = coeffs.value_getter.clone();
+        ZMatrix::from_ranges_values(&[range.clone(), range], move |&[i, j]|{
+            if j == n - 1 {
+                -valget(&[start + i])
+            } else if i == j + 1 {
+                Elem::one()
+            } else {
+                Elem::zero()
+            }
+        })
+    }
+
+    /// Extracts the anti-diagonal where `i + j == offset`.
+    ///
+    /// The result is indexed by the row `i`, so its element `i` is
+    /// `self[i, offset - i]`. Its range covers the rows for which both
+    /// indices fall within this matrix's ranges.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The constant sum of the row and column indices
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZVector holding the anti-diagonal
+    pub fn anti_diagonal(&self, offset: FiniteIndex) -> ZVector {
+        let cols = &self.index_ranges[1];
+        let next = OmegaIndex::Integer(offset + 1);
+        let rows = range_intersection(&self.index_ranges[0], &((next - cols.end)..(next - cols.start)));
+        let valget = self.value_getter.clone();
+        ZVector::from_ranges_values(&[rows], move |&[i]|{
+            valget(&[i, offset - i])
+        })
+    }
+
+    /// Scales the rows and columns of this matrix by two vectors.
+    ///
+    /// Element `[i, j]` of the result is `row_scale[i] * self[i, j] * col_scale[j]`,
+    /// which equals multiplying by diagonal matrices on both sides without
+    /// forming them. The ranges are intersected with those of the vectors.
+    ///
+    /// # Arguments
+    ///
+    /// * `row_scale` - Factors applied to each row
+    /// * `col_scale` - Factors applied to each column
+    ///
+    /// # Returns
+    ///
+    /// A new lazily evaluated ZMatrix
+    pub fn scale_rows_cols(&self, row_scale: &ZVector, col_scale: &ZVector) -> ZMatrix {
+        let ranges = [
+            range_intersection(&self.index_ranges[0], &row_scale.index_ranges[0]),
+            range_intersection(&self.index_ranges[1], &col_scale.index_ranges[0])
+        ];
+        let valget = self.value_getter.clone();
+        let row_getter = row_scale.value_getter.clone();
+        let col_getter = col_scale.value_getter.clone();
+        ZMatrix::from_ranges_values(&ranges, move |&[i, j]|{
+            row_getter(&[i]) * valget(&[i, j]) * col_getter(&[j])
+        })
+    }
+
+    /// Computes the partial trace over one factor of a bipartite space.
+    ///
+    /// The matrix is treated as an operator on a `dims.0 * dims.1` dimensional
+    /// tensor-product space, where row and column `start + a * dims.1 + b`
+    /// correspond to basis state `a` of the first subsystem and `b` of the
+    /// second. The other subsystem is traced out, leaving the reduced operator
+    /// on subsystem `keep`. Both dimensions must be finite with length
+    /// `dims.0 * dims.1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dims` - Dimensions of the two subsystems
+    /// * `keep` - The subsystem that is kept, either 0 or 1
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZMatrix over `0..dims.keep` in both dimensions
+    pub fn partial_trace_subsystem(&self, dims: (usize, usize), keep: usize) -> ZMatrix {
+        assert!(keep < 2, "partial_trace_subsystem: subsystem must be 0 or 1, got {}", keep);
+        let [rows, cols] = self.finite_index_ranges();
+        let total = (dims.0 * dims.1) as FiniteIndex;
+        assert!(rows.end - rows.start == total && cols.end - cols.start == total,
+            "partial_trace_subsystem requires a {}x{} matrix", total, total);
+        let (d0, d1) = (dims.0 as FiniteIndex, dims.1 as FiniteIndex);
+        let (kept, traced) = if keep == 0 { (d0, d1) } else { (d1, d0) };
+        let (row_start, col_start) = (rows.start, cols.start);
+        let valget = self.value_getter.clone();
+        let range = OmegaIndex::Integer(0)..OmegaIndex::Integer(kept);
+        ZMatrix::from_ranges_values(&[range.clone(), range], move |&[i, j]|{
+            (0..traced).map(|t| {
+                let (r, c) = if keep == 0 { (i * d1 + t, j * d1 + t) } else { (t * d1 + i, t * d1 + j) };
+                valget(&[row_start + r, col_start + c])
+            }).sum()
+        })
+    }
+
+    /// Returns the commutator `AB - BA` of this matrix `A` with another matrix `B`.
+    ///
+    /// Both matrices must be square with identical finite ranges. The result
+    /// is lazy, and evaluating one element costs a pass over a row and a
+    /// column of each matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The matrix `B`
+    ///
+    /// # Returns
+    ///
+    /// A new ZMatrix representing `AB - BA`
+    pub fn commutator(&self, other: &ZMatrix) -> ZMatrix {
+        assert!(self.index_ranges[0] == self.index_ranges[1], "commutator requires square matrices");
+        assert!(self.index_ranges == other.index_ranges, "commutator requires matrices with the same ranges");
+        self.matmul(other) - other.matmul(self)
+    }
+
+    /// Computes the matrix product with another matrix.
+    ///
+    /// The column range of this matrix must equal the row range of `rhs`
+    /// and be finite. The result ranges over the rows of this matrix and the
+    /// columns of `rhs`, which may be infinite. It is lazy, and evaluating
+    /// one element costs a pass over a row of this matrix and a column of `rhs`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The right factor
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZMatrix representing the product
+    pub fn matmul(&self, rhs: &ZMatrix) -> ZMatrix {
+        assert!(self.index_ranges[1] == rhs.index_ranges[0], "matmul requires the column range of the left factor to equal the row range of the right factor");
+        let inner = contraction_range(&self.index_ranges[1], "matmul");
+        let (a, b) = (self.value_getter.clone(), rhs.value_getter.clone());
+        ZMatrix::from_ranges_values(&[self.index_ranges[0].clone(), rhs.index_ranges[1].clone()], move |&[i, j]|{
+            inner.clone().map(|k| a(&[i, k]) * b(&[k, j])).sum()
+        })
+    }
+
+    /// Computes the product of this matrix with a vector.
+    ///
+    /// The column range of this matrix must equal the range of `v` and be
+    /// finite. The result ranges over the rows of this matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `v` - The vector to multiply
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZVector representing the product
+    pub fn matvec(&self, v: &ZVector) -> ZVector {
+        assert!(self.index_ranges[1] == v.index_ranges[0], "matvec requires the column range of the matrix to equal the range of the vector");
+        let inner = contraction_range(&self.index_ranges[1], "matvec");
+        let (a, x) = (self.value_getter.clone(), v.value_getter.clone());
+        ZVector::from_ranges_values(&[self.index_ranges[0].clone()], move |&[i]|{
+            inner.clone().map(|k| a(&[i, k]) * x(&[k])).sum()
+        })
+    }
+}
+
+/// Converts the range of a contracted dimension to a finite range.
+///
+/// Panics with a message naming the operation if the range is infinite.
+fn contraction_range(range: &Range<OmegaIndex>, op_name: &str) -> Range<FiniteIndex> {
+    match (range.start.to_finite(), range.end.to_finite()) {
+        (Some(start), Some(end)) => start..end,
+        _ => panic!("{} requires a finite contraction range", op_name)
+    }
+}
+
+#[test]
+fn test_matmul(){
+    use super::omega_int::OmegaInt::*;
+    // Infinite outer dimensions are allowed
+    let a = ZMatrix::from_ranges_values(&[MOmega..POmega, Integer(1)..Integer(3)], |&[i, k]| Elem::new((i * k) as f32, 0.0));
+    let b = ZMatrix::from_ranges_values(&[Integer(1)..Integer(3), Integer(0)..POmega], |&[k, j]| Elem::new(k as f32, j as f32));
+    let c = a.matmul(&b);
+    assert_eq!(c.get_index_ranges(), [MOmega..POmega, Integer(0)..POmega]);
+    // Sum over k = 1, 2 of a[-2, k] * b[k, 5] = (-2 k) * (k + 5 sqrt(-1))
+    assert_eq!(c.get_single_elem(&[-2, 5]), Elem::new(-10.0, -30.0));
+}
+
+#[test]
+#[should_panic(expected = "matmul requires a finite contraction range")]
+fn test_matmul_infinite_contraction(){
+    use super::omega_int::OmegaInt::*;
+    let a = ZMatrix::zeros(&[Integer(0)..Integer(2), Integer(0)..POmega]);
+    let b = ZMatrix::zeros(&[Integer(0)..POmega, Integer(0)..Integer(2)]);
+    let _ = a.matmul(&b);
+}
+
+/// Iterates over every multi-index of a finite box in row-major order.
+///
+/// The last dimension varies fastest. Yields nothing if any range is empty,
+/// and a single empty index for a 0-dimensional box.
+fn row_major_indices<const N: usize>(ranges: [Range<FiniteIndex>; N]) -> impl Iterator<Item = [FiniteIndex; N]> {
+    let mut next = if ranges.iter().any(|r| r.is_empty()) {
+        None
+    } else {
+        Some(ranges.clone().map(|r| r.start))
+    };
+    std::iter::from_fn(move || {
+        let current = next?;
+        let mut advanced = current;
+        next = None;
+        for axis in (0..N).rev() {
+            advanced[axis] += 1;
+            if advanced[axis] < ranges[axis].end {
+                next = Some(advanced);
+                break;
+            }
+            advanced[axis] = ranges[axis].start;
+        }
+        Some(current)
+    })
+}
+
+/// Converts omega ranges to plain integer ranges.
+///
+/// Panics if any range has an infinite endpoint.
+pub(crate) fn finite_ranges<const N: usize>(ranges: &[Range<OmegaIndex>; N]) -> [Range<FiniteIndex>; N] {
+    ranges.clone().map(|r| {
+        match (r.start, r.end) {
+            (OmegaIndex::Integer(start), OmegaIndex::Integer(end)) => start..end,
+            _ => panic!("ZTensor has an infinite dimension where a finite one is required")
+        }
+    })
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Computes the Frobenius inner product with another tensor.
+    ///
+    /// This is the sum over all indices of `self[idx] * conj(other[idx])`.
+    /// Both tensors must have the same finite index ranges.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The tensor whose elements are conjugated
+    ///
+    /// # Returns
+    ///
+    /// The inner product as a single element
+    pub fn full_inner(&self, other: &Self) -> Elem {
+        self.full_inner_with(other, |a, b| a * b.conj())
+    }
+
+    /// Computes the bilinear (non-conjugating) inner product with another tensor.
+    ///
+    /// This is the sum over all indices of `self[idx] * other[idx]`.
+    /// Both tensors must have the same finite index ranges.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The tensor to contract against
+    ///
+    /// # Returns
+    ///
+    /// The inner product as a single element
+    pub fn full_inner_no_conj(&self, other: &Self) -> Elem {
+        self.full_inner_with(other, |a, b| a * b)
+    }
+
+    /// Sums `combine(self[idx], other[idx])` over the shared finite index space.
+    fn full_inner_with(&self, other: &Self, combine: impl Fn(Elem, Elem) -> Elem) -> Elem {
+        assert!(self.index_ranges == other.index_ranges, "full_inner requires both tensors to have the same index ranges");
+        row_major_indices(self.finite_index_ranges())
+            .map(|idx| combine(self.get_single_elem(&idx), other.get_single_elem(&idx)))
+            .sum()
+    }
+}
+
+#[test]
+fn test_full_inner(){
+    use super::omega_int::OmegaInt::*;
+    let ranges = [Integer(0)..Integer(2), Integer(0)..Integer(3)];
+    let a = ZMatrix::from_ranges_values(&ranges, |&[i, j]|{Elem::new(i as f32, j as f32)});
+    let b = ZMatrix::from_ranges_values(&ranges, |&[i, j]|{Elem::new(1.0, (i - j) as f32)});
+
+    let mut expected = Elem::new(0.0, 0.0);
+    let mut expected_no_conj = Elem::new(0.0, 0.0);
+    for i in 0..2 {
+        for j in 0..3 {
+            expected += a.get_single_elem(&[i, j]) * b.get_single_elem(&[i, j]).conj();
+            expected_no_conj += a.get_single_elem(&[i, j]) * b.get_single_elem(&[i, j]);
+        }
+    }
+    assert_eq!(a.full_inner(&b), expected);
+    assert_eq!(a.full_inner_no_conj(&b), expected_no_conj);
+    // The inner product of a tensor with itself is its squared Frobenius norm
+    assert_eq!(a.full_inner(&a), Elem::new(13.0, 0.0));
+}
+
+impl<const N: usize, T> ZTensor<N, T> {
+    /// Returns the index ranges as plain integer ranges.
+    ///
+    /// Panics if any dimension has an infinite endpoint.
+    fn finite_index_ranges(&self) -> [Range<FiniteIndex>; N] {
+        finite_ranges(&self.index_ranges)
+    }
+
+    /// Iterates over the indices and elements of a finite tensor.
+    ///
+    /// The indices are visited in row-major order, with the last dimension
+    /// varying fastest, and elements are computed as the iterator advances.
+    /// Panics if any dimension is infinite.
+    ///
+    /// # Returns
+    ///
+    /// An iterator over `(index, element)` pairs
+    pub fn iter_indexed(&self) -> impl Iterator<Item = ([FiniteIndex; N], T)> + '_ {
+        row_major_indices(self.finite_index_ranges()).map(|idx| (idx, (self.value_getter)(&idx)))
+    }
+
+    /// Materializes the tensor into a flat row-major buffer.
+    ///
+    /// The last dimension varies fastest, matching `from_flat`.
+    /// Panics if any dimension is infinite.
+    ///
+    /// # Returns
+    ///
+    /// The buffer of elements and the length of each dimension
+    pub fn to_vec(&self) -> (Vec<T>, [usize; N]) {
+        let shape = self.finite_index_ranges().map(|r| (r.end - r.start).max(0) as usize);
+        (self.iter_indexed().map(|(_, value)| value).collect(), shape)
+    }
+
+    /// Creates a tensor backed by a flat row-major buffer of elements.
+    ///
+    /// The ranges start from 0 with the lengths given by `shape`, and the
+    /// last dimension varies fastest in the buffer.
+    /// Panics if the buffer length does not match the shape.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The elements in row-major order
+    /// * `shape` - Length of each dimension
+    ///
+    /// # Returns
+    ///
+    /// A ZTensor backed by the given buffer
+    pub fn from_flat(data: Vec<T>, shape: [usize; N]) -> Self where T: Clone + 'static {
+        assert_eq!(data.len(), shape.iter().product::<usize>(), "buffer length does not match the shape");
+        let data = Rc::new(data);
+        let ranges = shape.map(|l| OmegaIndex::Integer(0)..OmegaIndex::Integer(l as FiniteIndex));
+        Self::from_ranges_values(&ranges, move |idx: &[FiniteIndex; N]|{
+            data[row_major_offset(idx, &shape)].clone()
+        }).with_backing(BackingKind::Buffer)
+    }
+
+    /// Creates a tensor whose elements are all zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - Array of ranges for each dimension, which may be infinite
+    ///
+    /// # Returns
+    ///
+    /// A constant ZTensor of zeros
+    pub fn zeros(ranges: &[Range<OmegaIndex>; N]) -> Self where T: Zero + Clone + 'static {
+        Self::from_ranges_values(ranges, |_: &[FiniteIndex; N]| T::zero()).with_backing(BackingKind::Constant)
+    }
+
+    /// Creates a tensor whose elements are all one.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - Array of ranges for each dimension, which may be infinite
+    ///
+    /// # Returns
+    ///
+    /// A constant ZTensor of ones
+    pub fn ones(ranges: &[Range<OmegaIndex>; N]) -> Self where T: One + Clone + 'static {
+        Self::from_ranges_values(ranges, |_: &[FiniteIndex; N]| T::one()).with_backing(BackingKind::Constant)
+    }
+
+    /// Returns how this tensor computes its elements.
+    ///
+    /// Tensors derived from other tensors through combinators compute their
+    /// elements with a closure, whatever the backing of their sources.
+    pub fn backing(&self) -> BackingKind {
+        self.backing
+    }
+
+    /// Records how the value function of this tensor computes its elements.
+    pub(crate) fn with_backing(mut self, backing: BackingKind) -> Self {
+        self.backing = backing;
+        self
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Materializes the tensor into an interleaved `[re, im, re, im, ...]` buffer.
+    ///
+    /// Elements are written in row-major order, which is the layout expected
+    /// by most C FFT libraries. Panics if any dimension is infinite.
+    ///
+    /// # Returns
+    ///
+    /// A buffer holding twice as many values as the tensor has elements
+    pub fn to_interleaved_f32(&self) -> Vec<f32> {
+        self.iter_indexed().flat_map(|(_, value)| [value.re, value.im]).collect()
+    }
+
+    /// Creates a tensor from an interleaved `[re, im, re, im, ...]` buffer.
+    ///
+    /// This is the inverse of `to_interleaved_f32`. The buffer is read in
+    /// row-major order and the resulting ranges start from 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Interleaved real and imaginary components
+    /// * `shape` - Length of each dimension
+    ///
+    /// # Returns
+    ///
+    /// A ZTensor backed by the given buffer
+    pub fn from_interleaved_f32(data: Vec<f32>, shape: [usize; N]) -> Self {
+        let len: usize = shape.iter().product();
+        assert_eq!(data.len(), 2 * len, "interleaved buffer length does not match the shape");
+        Self::from_flat(data.chunks_exact(2).map(|c| Elem::new(c[0], c[1])).collect(), shape)
+    }
+}
+
+#[test]
+fn test_to_vec(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(-1)..Integer(1), Integer(5)..Integer(8)], |&[i, j]| Elem::new(i as f32, j as f32));
+    let (data, shape) = t.to_vec();
+    assert_eq!(shape, [2, 3]);
+    let expected: Vec<Elem> = [(-1, 5), (-1, 6), (-1, 7), (0, 5), (0, 6), (0, 7)].iter().map(|&(i, j)| Elem::new(i as f32, j as f32)).collect();
+    assert_eq!(data, expected);
+    let round_trip = ZMatrix::from_flat(data, shape);
+    assert_eq!(round_trip.get_single_elem(&[1, 2]), t.get_single_elem(&[0, 7]));
+    assert_eq!(round_trip.to_vec(), (expected, [2, 3]));
+}
+
+#[test]
+fn test_zeros_ones(){
+    use super::omega_int::OmegaInt::*;
+    let zeros = ZMatrix::zeros(&[MOmega..POmega, Integer(0)..Integer(3)]);
+    let ones = ZTensor::<2, f64>::ones(&[Integer(-2)..POmega, MOmega..POmega]);
+    assert_eq!(ones.get_index_ranges(), [Integer(-2)..POmega, MOmega..POmega]);
+    for [i, j] in [[-2, -100], [0, 0], [1000, 7]] {
+        assert_eq!(ones.get_single_elem(&[i, j]), 1.0);
+        assert_eq!(zeros.get_single_elem(&[i, j.rem_euclid(3)]), Elem::zero());
+    }
+    assert_eq!(ones.backing(), BackingKind::Constant);
+}
+
+#[test]
+fn test_backing(){
+    use super::omega_int::OmegaInt::*;
+    let zeros = ZMatrix::zeros(&[MOmega..POmega, Integer(0)..Integer(3)]);
+    assert_eq!(zeros.backing(), BackingKind::Constant);
+    assert_eq!(zeros.get_single_elem(&[-100, 2]), Elem::zero());
+
+    let flat = ZMatrix::from_flat((0..6).map(|x| Elem::new(x as f32, 0.0)).collect(), [2, 3]);
+    assert_eq!(flat.backing(), BackingKind::Buffer);
+    assert_eq!(flat.get_index_ranges(), [Integer(0)..Integer(2), Integer(0)..Integer(3)]);
+    assert_eq!(flat.get_single_elem(&[1, 0]), Elem::new(3.0, 0.0));
+    assert_eq!(flat.clone().backing(), BackingKind::Buffer);
+
+    assert_eq!(flat.conj_trans().backing(), BackingKind::Closure);
+    assert_eq!(ZVector::from_ranges_values(&[Integer(0)..Integer(1)], |_| Elem::one()).backing(), BackingKind::Closure);
+
+    let sparse = ZMatrix::from_sparse([MOmega..POmega, MOmega..POmega], vec![([0, 1], Elem::one())], Elem::zero());
+    assert_eq!(sparse.backing(), BackingKind::Buffer);
+    let square = ZMatrix::from_flat(vec![Elem::zero(), Elem::one(), Elem::zero(), Elem::zero()], [2, 2]);
+    assert_eq!(square.expm_series(4).backing(), BackingKind::Memoized);
+    let batch = ZTensor::<3>::batch_from_iter(std::iter::repeat(flat.clone()).take(2)).unwrap();
+    assert_eq!(batch.backing(), BackingKind::Buffer);
+}
+
+/// Computes the flat row-major offset of a 0-based index into a buffer of the given shape.
+fn row_major_offset<const N: usize>(index: &[FiniteIndex; N], shape: &[usize; N]) -> usize {
+    index.iter().zip(shape.iter()).fold(0, |offset, (&i, &l)| offset * l + i as usize)
+}
+
+#[test]
+fn test_iter_indexed(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(3)..Integer(5), Integer(-1)..Integer(1)], |&[i, j]| Elem::new(i as f32, j as f32));
+    let pairs: Vec<([FiniteIndex; 2], Elem)> = t.iter_indexed().collect();
+    assert_eq!(pairs, vec![
+        ([3, -1], Elem::new(3.0, -1.0)),
+        ([3, 0], Elem::new(3.0, 0.0)),
+        ([4, -1], Elem::new(4.0, -1.0)),
+        ([4, 0], Elem::new(4.0, 0.0))
+    ]);
+}
+
+#[test]
+fn test_interleaved_f32_round_trip(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(0)..Integer(2), Integer(0)..Integer(3)], |&[i, j]|{
+        Elem::new(i as f32, -(j as f32))
+    });
+    let data = t.to_interleaved_f32();
+    assert_eq!(data, vec![0., 0., 0., -1., 0., -2., 1., 0., 1., -1., 1., -2.]);
+
+    let t2 = ZMatrix::from_interleaved_f32(data, [2, 3]);
+    assert_eq!(t2.get_index_ranges(), t.get_index_ranges());
+    for i in 0..2 {
+        for j in 0..3 {
+            assert_eq!(t2.get_single_elem(&[i, j]), t.get_single_elem(&[i, j]));
+        }
+    }
+}
+
+impl<const N: usize, T: 'static> ZTensor<N, T> {
+    /// Overlays a different value function on a sub-region of this tensor.
+    ///
+    /// The result reads `patch_getter` for indices inside `region` and the
+    /// original value function everywhere else. Nothing is materialized.
+    ///
+    /// # Arguments
+    ///
+    /// * `region` - Ranges of the patched region, which may be infinite
+    /// * `patch_getter` - Function computing the elements inside the region
+    ///
+    /// # Returns
+    ///
+    /// A new ZTensor with the same ranges as this one
+    pub fn patch<F: CloneableFn<N, T> + 'static>(&self, region: [Range<OmegaIndex>; N], patch_getter: F) -> Self {
         let valget = self.value_getter.clone();
-        ZMatrix::from_ranges_values(&[self.index_ranges[1].clone(), self.index_ranges[0].clone()], move|&[i, j]|{
-            let val = valget(&[j, i]);
-            val.conj()
+        let patch_getter: Box<dyn CloneableFn<N, T>> = Box::new(patch_getter);
+        Self::from_ranges_values(&self.index_ranges, move |idx: &[FiniteIndex; N]|{
+            if region.iter().zip(idx.iter()).all(|(r, &i)| omega_range_contains(r, i)) {
+                patch_getter(idx)
+            } else {
+                valget(idx)
+            }
         })
     }
 }
+
+#[test]
+fn test_patch(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(0)..Integer(4), Integer(0)..Integer(4)], |&[i, j]|{
+        Elem::new((i * 10 + j) as f32, 0.0)
+    });
+    let p = t.patch([Integer(1)..Integer(3), Integer(2)..Integer(4)], |_: &[FiniteIndex; 2]|{Elem::new(-1.0, 0.0)});
+    assert_eq!(p.get_index_ranges(), t.get_index_ranges());
+    for [i, j] in [[1, 2], [1, 3], [2, 2], [2, 3]] {
+        assert_eq!(p.get_single_elem(&[i, j]), Elem::new(-1.0, 0.0));
+    }
+    for [i, j] in [[0, 2], [3, 3], [1, 1], [2, 0], [0, 0]] {
+        assert_eq!(p.get_single_elem(&[i, j]), t.get_single_elem(&[i, j]));
+    }
+}
+
+impl<const N: usize, T> ZTensor<N, T> {
+    /// Returns the total number of elements in the tensor.
+    ///
+    /// This is the product of the lengths of all dimensions, where reversed
+    /// ranges count as empty. It is `POmega` if any dimension is infinite
+    /// and no dimension is empty, or if the product overflows.
+    pub fn numel(&self) -> OmegaIndex {
+        let lengths = self.index_ranges.clone().map(|r| {
+            match (r.start, r.end) {
+                (OmegaIndex::Integer(start), OmegaIndex::Integer(end)) if start >= end => OmegaIndex::zero(),
+                (OmegaIndex::Integer(start), OmegaIndex::Integer(end)) => end.checked_sub(start).map_or(OmegaIndex::POmega, OmegaIndex::Integer),
+                (OmegaIndex::MOmega, OmegaIndex::MOmega) | (OmegaIndex::POmega, _) | (_, OmegaIndex::MOmega) => OmegaIndex::zero(),
+                _ => OmegaIndex::POmega
+            }
+        });
+        if lengths.iter().any(|l| l.is_zero()) {
+            return OmegaIndex::zero();
+        }
+        lengths.into_iter().fold(OmegaIndex::one(), |acc, l| acc.checked_mul(&l).unwrap_or(OmegaIndex::POmega))
+    }
+
+    /// Gets a single element, checking that the indices are within the ranges.
+    ///
+    /// Unlike `get_single_elem`, this never calls the value function
+    /// outside of the ranges of the tensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - Array of indices, one for each dimension
+    ///
+    /// # Returns
+    ///
+    /// The element at the specified indices, or None if any index is out of range
+    pub fn try_get(&self, indices: &[FiniteIndex; N]) -> Option<T> {
+        if self.index_ranges.iter().zip(indices.iter()).all(|(r, &i)| omega_range_contains(r, i)) {
+            Some((self.value_getter)(indices))
+        } else {
+            None
+        }
+    }
+}
+
+#[test]
+fn test_try_get(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(0)..Integer(3), Integer(-2)..POmega], |&[i, j]| Elem::new(i as f32, j as f32));
+    assert_eq!(t.try_get(&[0, -2]), Some(Elem::new(0.0, -2.0)));
+    assert_eq!(t.try_get(&[2, 1_000_000]), Some(Elem::new(2.0, 1_000_000.0)));
+    assert_eq!(t.try_get(&[3, 0]), None);
+    assert_eq!(t.try_get(&[-1, 0]), None);
+    // Only the lower bound can reject an index in the second dimension
+    assert_eq!(t.try_get(&[1, -3]), None);
+    assert_eq!(t.try_get(&[1, FiniteIndex::MAX]), Some(Elem::new(1.0, FiniteIndex::MAX as f32)));
+}
+
+#[test]
+fn test_numel(){
+    use super::omega_int::OmegaInt::*;
+    let finite = ZTensor::<3>::from_ranges_values(&[Integer(0)..Integer(2), Integer(-1)..Integer(2), Integer(5)..Integer(9)], |_|{Elem::new(0.0, 0.0)});
+    assert_eq!(finite.numel(), Integer(24));
+    let half_infinite = ZMatrix::from_ranges_values(&[Integer(0)..Integer(2), Integer(0)..POmega], |_|{Elem::new(0.0, 0.0)});
+    assert_eq!(half_infinite.numel(), POmega);
+    let empty = ZMatrix::from_ranges_values(&[Integer(3)..Integer(3), MOmega..POmega], |_|{Elem::new(0.0, 0.0)});
+    assert_eq!(empty.numel(), Integer(0));
+    let reversed = ZMatrix::from_ranges_values(&[Integer(3)..Integer(1), Integer(0)..Integer(4)], |_|{Elem::new(0.0, 0.0)});
+    assert_eq!(reversed.numel(), Integer(0));
+    let reversed_infinite = ZMatrix::from_ranges_values(&[MOmega..MOmega, POmega..Integer(0)], |_|{Elem::new(0.0, 0.0)});
+    assert_eq!(reversed_infinite.numel(), Integer(0));
+    let overflowing = ZTensor::<3>::from_ranges_values(&[Integer(0)..Integer(1 << 30), Integer(0)..Integer(1 << 30), Integer(0)..Integer(1 << 30)], |_|{Elem::new(0.0, 0.0)});
+    assert_eq!(overflowing.numel(), POmega);
+    let widest = ZVector::from_ranges_values(&[Integer(FiniteIndex::MIN)..Integer(FiniteIndex::MAX)], |_|{Elem::new(0.0, 0.0)});
+    assert_eq!(widest.numel(), POmega);
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Replaces the elements selected by a boolean mask with a constant.
+    ///
+    /// The result yields `value` where `mask` is true and the original
+    /// element where it is false. Its ranges are the intersection of the
+    /// ranges of this tensor and the mask.
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - Boolean tensor selecting the elements to replace
+    /// * `value` - The value written where the mask is true
+    ///
+    /// # Returns
+    ///
+    /// A new lazily evaluated ZTensor
+    pub fn masked_fill<M>(&self, mask: &M, value: Elem) -> Self
+    where M: ZTensorLike<N, DType = bool> + Clone + 'static {
+        let mask_ranges = mask.get_index_ranges();
+        let mut ranges = self.index_ranges.clone();
+        for (r, m) in ranges.iter_mut().zip(mask_ranges.iter()) {
+            *r = range_intersection(r, m);
+        }
+        let valget = self.value_getter.clone();
+        let mask = mask.clone();
+        Self::from_ranges_values(&ranges, move |idx: &[FiniteIndex; N]|{
+            if mask.get_single_elem(idx) {
+                value
+            } else {
+                valget(idx)
+            }
+        })
+    }
+}
+
+#[test]
+fn test_masked_fill(){
+    use super::omega_int::OmegaInt::*;
+    let upper_triangle = ZTensor::<2, bool>::from_ranges_values(&[MOmega..POmega, MOmega..POmega], |&[i, j]| j > i);
+    let t = ZMatrix::from_ranges_values(&[Integer(0)..Integer(3), Integer(0)..Integer(3)], |&[i, j]|{
+        Elem::new((i * 3 + j + 1) as f32, 0.0)
+    });
+    let filled = t.masked_fill(&upper_triangle, Elem::new(0.0, 0.0));
+    assert_eq!(filled.get_index_ranges(), t.get_index_ranges());
+    for i in 0..3 {
+        for j in 0..3 {
+            if j > i {
+                assert_eq!(filled.get_single_elem(&[i, j]), Elem::new(0.0, 0.0));
+            } else {
+                assert_eq!(filled.get_single_elem(&[i, j]), t.get_single_elem(&[i, j]));
+            }
+        }
+    }
+}
+
+impl<const N: usize, T> ZTensor<N, T> {
+    /// Computes the per-dimension intersection of the ranges of many tensors.
+    ///
+    /// Dimensions where the ranges do not overlap get an empty range.
+    /// An empty slice of tensors yields `MOmega..POmega` in every dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensors` - The tensors whose ranges are intersected
+    ///
+    /// # Returns
+    ///
+    /// Array of ranges shared by all the tensors
+    pub fn common_ranges(tensors: &[&ZTensor<N, T>]) -> [Range<OmegaIndex>; N] {
+        let full: [Range<OmegaIndex>; N] = std::array::from_fn(|_| OmegaIndex::MOmega..OmegaIndex::POmega);
+        tensors.iter().fold(full, |acc, t| {
+            std::array::from_fn(|d| range_intersection(&acc[d], &t.index_ranges[d]))
+        })
+    }
+}
+
+#[test]
+fn test_common_ranges(){
+    use super::omega_int::OmegaInt::*;
+    let zero = |_: &[FiniteIndex; 2]|{Elem::new(0.0, 0.0)};
+    let a = ZMatrix::from_ranges_values(&[Integer(0)..Integer(10), MOmega..Integer(4)], zero);
+    let b = ZMatrix::from_ranges_values(&[Integer(2)..Integer(8), Integer(-3)..POmega], zero);
+    let c = ZMatrix::from_ranges_values(&[Integer(-5)..Integer(6), Integer(1)..Integer(9)], zero);
+    assert_eq!(ZMatrix::common_ranges(&[&a, &b, &c]), [Integer(2)..Integer(6), Integer(1)..Integer(4)]);
+
+    let disjoint = ZMatrix::from_ranges_values(&[Integer(20)..Integer(30), Integer(0)..Integer(1)], zero);
+    let ranges = ZMatrix::common_ranges(&[&a, &disjoint]);
+    assert_eq!(ranges[0].start, ranges[0].end);
+    assert_eq!(ZMatrix::common_ranges(&[]), [MOmega..POmega, MOmega..POmega]);
+}
+
+#[cfg(feature = "rand")]
+impl<const N: usize> ZTensor<N> {
+    /// Evaluates the tensor at a uniformly random index.
+    ///
+    /// Each coordinate is drawn uniformly from its dimension's range, which
+    /// makes the multi-index uniform over the whole tensor. Panics if any
+    /// dimension is infinite or empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - Random number generator used to draw the index
+    ///
+    /// # Returns
+    ///
+    /// The sampled index together with the element stored there
+    pub fn sample_random(&self, rng: &mut impl rand::Rng) -> ([FiniteIndex; N], Elem) {
+        let ranges = self.finite_index_ranges();
+        let idx = ranges.map(|r| {
+            assert!(!r.is_empty(), "cannot sample from a tensor with an empty dimension");
+            rng.gen_range(r)
+        });
+        (idx, self.get_single_elem(&idx))
+    }
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_sample_random(){
+    use super::omega_int::OmegaInt::*;
+    use rand::SeedableRng;
+    let t = ZMatrix::from_ranges_values(&[Integer(-3)..Integer(2), Integer(4)..Integer(7)], |&[i, j]|{
+        Elem::new(i as f32, j as f32)
+    });
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    for _ in 0..100 {
+        let ([i, j], value) = t.sample_random(&mut rng);
+        assert!((-3..2).contains(&i));
+        assert!((4..7).contains(&j));
+        assert_eq!(value, Elem::new(i as f32, j as f32));
+    }
+}
+
+impl<const N: usize> ZTensor<N, f32> {
+    /// Computes the running maximum along one dimension.
+    ///
+    /// Output element `i` along `axis` is the maximum of all input elements
+    /// up to and including `i`. The scanned dimension must be finite, while
+    /// the other dimensions may be infinite.
+    ///
+    /// The result is lazy, so evaluating an element costs one input read per
+    /// preceding position along `axis`. Use `cummax_materialized` when many
+    /// elements will be read.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - The dimension to scan along
+    ///
+    /// # Returns
+    ///
+    /// A new ZTensor with the same ranges as this one
+    pub fn cummax(&self, axis: usize) -> Self {
+        assert!(axis < N, "cummax axis {} is out of range for a {}-dimensional tensor", axis, N);
+        let start = match (self.index_ranges[axis].start, self.index_ranges[axis].end) {
+            (OmegaIndex::Integer(start), OmegaIndex::Integer(_)) => start,
+            _ => panic!("cummax requires the scanned dimension to be finite")
+        };
+        let valget = self.value_getter.clone();
+        Self::from_ranges_values(&self.index_ranges, move |idx: &[FiniteIndex; N]|{
+            let mut scan_idx = *idx;
+            scan_idx[axis] = start;
+            let mut acc = valget(&scan_idx);
+            for k in start + 1..=idx[axis] {
+                scan_idx[axis] = k;
+                acc = acc.max(valget(&scan_idx));
+            }
+            acc
+        })
+    }
+
+    /// Computes the running maximum along one dimension eagerly.
+    ///
+    /// This gives the same values as `cummax` but evaluates the whole tensor
+    /// once up front, in time linear in the number of elements. All
+    /// dimensions must be finite.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - The dimension to scan along
+    ///
+    /// # Returns
+    ///
+    /// A new ZTensor backed by the computed values
+    pub fn cummax_materialized(&self, axis: usize) -> Self {
+        assert!(axis < N, "cummax axis {} is out of range for a {}-dimensional tensor", axis, N);
+        let ranges = self.finite_index_ranges();
+        let starts = ranges.clone().map(|r| r.start);
+        let shape = ranges.clone().map(|r| (r.end - r.start).max(0) as usize);
+        let stride: usize = shape[axis + 1..].iter().product();
+        let mut values: Vec<f32> = Vec::with_capacity(shape.iter().product());
+        for idx in row_major_indices(ranges) {
+            let value = self.get_single_elem(&idx);
+            if idx[axis] == starts[axis] {
+                values.push(value);
+            } else {
+                values.push(values[values.len() - stride].max(value));
+            }
+        }
+        let values = Rc::new(values);
+        Self::from_ranges_values(&self.index_ranges, move |idx: &[FiniteIndex; N]|{
+            let rel: [FiniteIndex; N] = std::array::from_fn(|d| idx[d] - starts[d]);
+            values[row_major_offset(&rel, &shape)]
+        }).with_backing(BackingKind::Buffer)
+    }
+}
+
+#[test]
+fn test_cummax(){
+    use super::omega_int::OmegaInt::*;
+    let data = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+    let v = ZTensor::<1, f32>::from_ranges_values(&[Integer(2)..Integer(10)], move |&[i]| data[(i - 2) as usize]);
+    let lazy = v.cummax(0);
+    let materialized = v.cummax_materialized(0);
+    let mut running = f32::MIN;
+    for i in 2..10 {
+        running = running.max(data[(i - 2) as usize]);
+        assert_eq!(lazy.get_single_elem(&[i]), running);
+        assert_eq!(materialized.get_single_elem(&[i]), running);
+    }
+
+    // Scanning the column dimension of a matrix works row by row
+    let m = ZTensor::<2, f32>::from_ranges_values(&[Integer(0)..Integer(2), Integer(0)..Integer(3)], |&[i, j]|{
+        if i == 0 { j as f32 } else { -(j as f32) }
+    });
+    let scanned = m.cummax_materialized(1);
+    assert_eq!(scanned.get_single_elem(&[0, 2]), 2.0);
+    assert_eq!(scanned.get_single_elem(&[1, 2]), 0.0);
+    assert_eq!(m.cummax(0).get_single_elem(&[1, 2]), 2.0);
+    assert_eq!(scanned.backing(), BackingKind::Buffer);
+}
+
+#[test]
+#[should_panic(expected = "cummax axis 2 is out of range for a 2-dimensional tensor")]
+fn test_cummax_axis_out_of_range(){
+    use super::omega_int::OmegaInt::*;
+    let m = ZTensor::<2, f32>::from_ranges_values(&[Integer(0)..Integer(2), Integer(0)..Integer(3)], |_| 0.0);
+    let _ = m.cummax_materialized(2);
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Returns the element-wise reciprocal `1 / self[idx]`.
+    ///
+    /// Elements equal to zero do not panic; they produce infinite or NaN
+    /// components following floating point division.
+    ///
+    /// # Returns
+    ///
+    /// A new lazily evaluated ZTensor with the same ranges
+    pub fn recip(&self) -> Self {
+        self.map(|x| x.inv())
+    }
+}
+
+#[test]
+fn test_recip(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(-2)..Integer(3), Integer(-2)..Integer(3)], |&[i, j]|{
+        Elem::new(i as f32, j as f32)
+    });
+    let r = t.recip();
+    assert_eq!(r.get_single_elem(&[0, 2]), Elem::new(0.0, -0.5));
+    let rr = r.recip();
+    for i in -2..3 {
+        for j in -2..3 {
+            if (i, j) == (0, 0) {
+                let z = r.get_single_elem(&[i, j]);
+                assert!(z.re.is_nan() || z.re.is_infinite());
+                continue;
+            }
+            assert!((rr.get_single_elem(&[i, j]) - t.get_single_elem(&[i, j])).norm() < 1e-5);
+        }
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Creates a tensor whose value function reads from shared state.
+    ///
+    /// The state is captured through an `Arc`, so cloning the tensor (or
+    /// deriving new tensors from it) only clones the handle, not the state.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - Array of ranges for each dimension
+    /// * `state` - Shared state passed to every call of `f`
+    /// * `f` - Function computing the element from the state and the indices
+    ///
+    /// # Returns
+    ///
+    /// A new ZTensor backed by the shared state
+    pub fn from_shared<S, F>(ranges: &[Range<OmegaIndex>; N], state: Arc<S>, f: F) -> Self
+    where S: Send + Sync + 'static, F: Fn(&S, &[FiniteIndex; N]) -> Elem + Clone + 'static {
+        Self::from_ranges_values(ranges, move |idx: &[FiniteIndex; N]|{
+            f(&state, idx)
+        })
+    }
+}
+
+#[test]
+fn test_from_shared(){
+    use super::omega_int::OmegaInt::*;
+    let table: Arc<Vec<Elem>> = Arc::new((0..5).map(|i| Elem::new(i as f32, 1.0)).collect());
+    let t = ZVector::from_shared(&[Integer(0)..Integer(5)], table.clone(), |table: &Vec<Elem>, &[i]: &[FiniteIndex; 1]|{
+        table[i as usize] * 2.0
+    });
+    assert_eq!(Arc::strong_count(&table), 2);
+    let copy = t.clone();
+    assert_eq!(Arc::strong_count(&table), 3);
+    assert_eq!(t.get_single_elem(&[3]), Elem::new(6.0, 2.0));
+    assert_eq!(copy.get_single_elem(&[4]), Elem::new(8.0, 2.0));
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Finds the dimension with the greatest finite length.
+    ///
+    /// Infinite dimensions are ignored, reversed ranges count as empty, and
+    /// lengths too large for `FiniteIndex` saturate. On ties the lowest axis
+    /// is returned.
+    ///
+    /// # Returns
+    ///
+    /// The axis index, or None if every dimension is infinite
+    pub fn largest_finite_axis(&self) -> Option<usize> {
+        let mut best: Option<(usize, FiniteIndex)> = None;
+        for (axis, r) in self.index_ranges.iter().enumerate() {
+            if let (OmegaIndex::Integer(start), OmegaIndex::Integer(end)) = (r.start, r.end) {
+                let len = end.saturating_sub(start).max(0);
+                if best.map_or(true, |(_, best_len)| len > best_len) {
+                    best = Some((axis, len));
+                }
+            }
+        }
+        best.map(|(axis, _)| axis)
+    }
+}
+
+#[test]
+fn test_largest_finite_axis(){
+    use super::omega_int::OmegaInt::*;
+    let zero = |_: &[FiniteIndex; 3]|{Elem::new(0.0, 0.0)};
+    let t = ZTensor::<3>::from_ranges_values(&[Integer(0)..Integer(3), Integer(-2)..Integer(5), Integer(0)..POmega], zero);
+    assert_eq!(t.largest_finite_axis(), Some(1));
+    let infinite = ZTensor::<3>::from_ranges_values(&[MOmega..POmega, MOmega..Integer(0), Integer(0)..POmega], zero);
+    assert_eq!(infinite.largest_finite_axis(), None);
+    let reversed = ZTensor::<3>::from_ranges_values(&[Integer(5)..Integer(-5), Integer(0)..Integer(0), Integer(0)..POmega], zero);
+    assert_eq!(reversed.largest_finite_axis(), Some(0));
+    let widest = ZTensor::<3>::from_ranges_values(&[Integer(0)..Integer(3), Integer(FiniteIndex::MIN)..Integer(FiniteIndex::MAX), Integer(0)..Integer(7)], zero);
+    assert_eq!(widest.largest_finite_axis(), Some(1));
+}
+
+#[test]
+fn test_commutator(){
+    use super::omega_int::OmegaInt::*;
+    let ranges = [Integer(1)..Integer(4), Integer(1)..Integer(4)];
+    let a = ZMatrix::from_ranges_values(&ranges, |&[i, j]|{Elem::new((i * j) as f32, (i - j) as f32)});
+    let b = ZMatrix::from_ranges_values(&ranges, |&[i, j]|{Elem::new(if i == j { i as f32 } else { 1.0 }, 0.0)});
+    let aa = a.commutator(&a);
+    let ab = a.commutator(&b);
+    let ba = b.commutator(&a);
+    for i in 1..4 {
+        for j in 1..4 {
+            assert_eq!(aa.get_single_elem(&[i, j]), Elem::new(0.0, 0.0));
+            assert_eq!(ab.get_single_elem(&[i, j]), -ba.get_single_elem(&[i, j]));
+        }
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Zeroes out the elements whose magnitude is below a threshold.
+    ///
+    /// Elements with `|z| >= min_magnitude` are kept unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_magnitude` - Smallest magnitude that survives the thresholding
+    ///
+    /// # Returns
+    ///
+    /// A new lazily evaluated ZTensor with the same ranges
+    pub fn threshold(&self, min_magnitude: f32) -> Self {
+        self.map(move |x| if x.norm() >= min_magnitude { x } else { Elem::zero() })
+    }
+}
+
+#[test]
+fn test_threshold(){
+    use super::omega_int::OmegaInt::*;
+    let data = [Elem::new(0.01, 0.0), Elem::new(3.0, 4.0), Elem::new(0.0, -0.2), Elem::new(-0.5, 0.0), Elem::new(0.6, 0.8)];
+    let t = ZVector::from_ranges_values(&[Integer(0)..Integer(5)], move |&[i]|{data[i as usize]});
+    let thresholded = t.threshold(0.5);
+    assert_eq!(thresholded.get_single_elem(&[0]), Elem::zero());
+    assert_eq!(thresholded.get_single_elem(&[1]), Elem::new(3.0, 4.0));
+    assert_eq!(thresholded.get_single_elem(&[2]), Elem::zero());
+    assert_eq!(thresholded.get_single_elem(&[3]), Elem::new(-0.5, 0.0));
+    assert_eq!(thresholded.get_single_elem(&[4]), Elem::new(0.6, 0.8));
+}
+
+/// Error returned by `batch_from_iter` when a matrix does not share the ranges of the batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchShapeError {
+    /// Position of the offending matrix in the iterator
+    pub index: usize,
+    /// First dimension of the matrix whose range differs
+    pub axis: usize,
+    /// Range of that dimension in the first matrix of the batch
+    pub expected: Range<OmegaIndex>,
+    /// Range of that dimension in the offending matrix
+    pub found: Range<OmegaIndex>
+}
+
+impl fmt::Display for BatchShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "matrix {} of the batch has range {:?} along axis {}, but the batch has range {:?}", self.index, self.found, self.axis, self.expected)
+    }
+}
+
+impl std::error::Error for BatchShapeError {}
+
+impl ZTensor<3> {
+    /// Stacks matrices from an iterator along a new leading batch dimension.
+    ///
+    /// The matrices are collected into a shared buffer, and the batch
+    /// dimension of the result ranges over `0..count`, selecting the matrix.
+    /// All matrices must have the same ranges, which become the two trailing
+    /// dimensions of the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `iter` - Iterator producing the matrices to stack
+    ///
+    /// # Returns
+    ///
+    /// The stacked tensor, or an error describing the first matrix whose
+    /// ranges differ from the first one
+    pub fn batch_from_iter<I: Iterator<Item = ZMatrix>>(iter: I) -> Result<ZTensor<3>, BatchShapeError> {
+        let mut matrices: Vec<ZMatrix> = Vec::new();
+        for (index, mat) in iter.enumerate() {
+            if let Some(first) = matrices.first() {
+                if let Some(axis) = (0..2).find(|&d| first.index_ranges[d] != mat.index_ranges[d]) {
+                    return Err(BatchShapeError { index, axis, expected: first.index_ranges[axis].clone(), found: mat.index_ranges[axis].clone() });
+                }
+            }
+            matrices.push(mat);
+        }
+        let [rows, cols] = match matrices.first() {
+            Some(first) => first.index_ranges.clone(),
+            None => [OmegaIndex::Integer(0)..OmegaIndex::Integer(0), OmegaIndex::Integer(0)..OmegaIndex::Integer(0)]
+        };
+        let batch = OmegaIndex::Integer(0)..OmegaIndex::Integer(matrices.len() as FiniteIndex);
+        let matrices = Rc::new(matrices);
+        Ok(ZTensor::<3>::from_ranges_values(&[batch, rows, cols], move |&[b, i, j]|{
+            matrices[b as usize].get_single_elem(&[i, j])
+        }).with_backing(BackingKind::Buffer))
+    }
+}
+
+#[test]
+fn test_batch_from_iter(){
+    use super::omega_int::OmegaInt::*;
+    let ranges = [Integer(0)..Integer(2), Integer(1)..Integer(3)];
+    let batch = ZTensor::<3>::batch_from_iter((0..3).map(|b|{
+        ZMatrix::from_ranges_values(&ranges, move |&[i, j]|{Elem::new((b * 100 + i * 10 + j) as f32, 0.0)})
+    })).unwrap();
+    assert_eq!(batch.get_index_ranges(), [Integer(0)..Integer(3), Integer(0)..Integer(2), Integer(1)..Integer(3)]);
+    for b in 0..3 {
+        assert_eq!(batch.get_single_elem(&[b, 1, 2]), Elem::new((b * 100 + 12) as f32, 0.0));
+    }
+
+    let mismatched = ZTensor::<3>::batch_from_iter((0..3).map(|b|{
+        let end = if b == 2 { 4 } else { 3 };
+        ZMatrix::from_ranges_values(&[Integer(0)..Integer(2), Integer(1)..Integer(end)], |_|{Elem::zero()})
+    }));
+    let err = mismatched.err().unwrap();
+    assert_eq!(err, BatchShapeError { index: 2, axis: 1, expected: Integer(1)..Integer(3), found: Integer(1)..Integer(4) });
+    assert!(err.to_string().starts_with("matrix 2 of the batch has range"));
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Sums over every dimension except one.
+    ///
+    /// Entry `k` of the result is the sum of all elements whose index along
+    /// `keep` is `k`, which gives the marginal along that dimension. All the
+    /// summed dimensions must be finite, while the kept one may be infinite.
+    ///
+    /// # Arguments
+    ///
+    /// * `keep` - The dimension that indexes the result
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZVector over the range of the kept dimension
+    pub fn sum_over_all_but(&self, keep: usize) -> ZVector {
+        assert!(keep < N, "sum_over_all_but: axis {} out of bounds for a {}-dimensional tensor", keep, N);
+        let summed: [Range<FiniteIndex>; N] = std::array::from_fn(|d| {
+            if d == keep {
+                return 0..1;
+            }
+            match (self.index_ranges[d].start, self.index_ranges[d].end) {
+                (OmegaIndex::Integer(start), OmegaIndex::Integer(end)) => start..end,
+                _ => panic!("sum_over_all_but requires the summed dimensions to be finite")
+            }
+        });
+        let valget = self.value_getter.clone();
+        ZVector::from_ranges_values(&[self.index_ranges[keep].clone()], move |&[k]|{
+            row_major_indices(summed.clone()).map(|mut idx| {
+                idx[keep] = k;
+                valget(&idx)
+            }).sum()
+        })
+    }
+}
+
+#[test]
+fn test_sum_over_all_but(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(0)..Integer(2), Integer(1)..Integer(4)], |&[i, j]|{
+        Elem::new((i * 10 + j) as f32, 1.0)
+    });
+    let row_sums = t.sum_over_all_but(0);
+    assert_eq!(row_sums.get_index_ranges(), [Integer(0)..Integer(2)]);
+    assert_eq!(row_sums.get_single_elem(&[0]), Elem::new(6.0, 3.0));
+    assert_eq!(row_sums.get_single_elem(&[1]), Elem::new(36.0, 3.0));
+    let col_sums = t.sum_over_all_but(1);
+    assert_eq!(col_sums.get_index_ranges(), [Integer(1)..Integer(4)]);
+    assert_eq!(col_sums.get_single_elem(&[1]), Elem::new(12.0, 2.0));
+    assert_eq!(col_sums.get_single_elem(&[3]), Elem::new(16.0, 2.0));
+
+    // The kept dimension may be infinite
+    let half_infinite = ZMatrix::from_ranges_values(&[MOmega..POmega, Integer(0)..Integer(3)], |&[i, j]|{
+        Elem::new((i * j) as f32, 0.0)
+    });
+    assert_eq!(half_infinite.sum_over_all_but(0).get_single_elem(&[-7]), Elem::new(-21.0, 0.0));
+}
+
+impl ZVector {
+    /// Creates an unnormalized Gaussian kernel over the whole integer line.
+    ///
+    /// The element at `i` is `exp(-i^2 / (2 sigma^2))` with zero imaginary
+    /// part. Slicing the result to a finite range gives a usable kernel.
+    ///
+    /// # Arguments
+    ///
+    /// * `sigma` - Standard deviation of the Gaussian
+    ///
+    /// # Returns
+    ///
+    /// A ZVector ranging over `MOmega..POmega`
+    pub fn gaussian_kernel_1d(sigma: f32) -> ZVector {
+        ZVector::from_ranges_values(&[OmegaIndex::MOmega..OmegaIndex::POmega], move |&[i]|{
+            let x = i as f32;
+            Elem::new((-(x * x) / (2.0 * sigma * sigma)).exp(), 0.0)
+        })
+    }
+}
+
+#[test]
+fn test_gaussian_kernel_1d(){
+    use super::omega_int::OmegaInt::*;
+    let kernel = ZVector::gaussian_kernel_1d(1.5).get_slice(&[Integer(-3)..Integer(4)]);
+    assert_eq!(kernel.get_single_elem(&[0]), Elem::new(1.0, 0.0));
+    for i in 1..4 {
+        assert_eq!(kernel.get_single_elem(&[i]), kernel.get_single_elem(&[-i]));
+        assert!(kernel.get_single_elem(&[i]).re < kernel.get_single_elem(&[i - 1]).re);
+        assert_eq!(kernel.get_single_elem(&[i]).im, 0.0);
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Creates a ZTensor from any other tensor-like source.
+    ///
+    /// The source is cloned into the value function and its ranges are
+    /// adopted, so the result reads through to the source lazily.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The tensor-like object to adapt
+    ///
+    /// # Returns
+    ///
+    /// A ZTensor with the same ranges and elements as `src`
+    pub fn from_ztensor_like<T: ZTensorLike<N, DType = Elem> + Clone + 'static>(src: &T) -> ZTensor<N> {
+        let src = src.clone();
+        let ranges = src.get_index_ranges();
+        Self::from_ranges_values(&ranges, move |idx: &[FiniteIndex; N]|{
+            src.get_single_elem(idx)
+        })
+    }
+}
+
+#[test]
+fn test_from_ztensor_like(){
+    use super::omega_int::OmegaInt::*;
+    #[derive(Clone)]
+    struct Hilbert;
+    impl ZTensorLike<2> for Hilbert {
+        type DType = Elem;
+        fn get_single_elem(&self, indices: &[FiniteIndex; 2]) -> Elem {
+            Elem::new(1.0 / (indices[0] + indices[1] + 1) as f32, 0.0)
+        }
+        fn get_index_ranges(&self) -> [Range<OmegaIndex>; 2] {
+            [Integer(0)..POmega, Integer(0)..POmega]
+        }
+    }
+
+    let t = ZMatrix::from_ztensor_like(&Hilbert);
+    assert_eq!(t.get_index_ranges(), Hilbert.get_index_ranges());
+    assert_eq!(t.get_single_elem(&[1, 2]), Elem::new(0.25, 0.0));
+    assert_eq!(t.conj_trans().get_single_elem(&[3, 0]), Elem::new(0.25, 0.0));
+}
+
+impl<const N: usize> ZTensor<N, f32> {
+    /// Applies the logistic sigmoid `1 / (1 + exp(-x))` to each element.
+    ///
+    /// # Returns
+    ///
+    /// A new lazily evaluated ZTensor with the same ranges
+    pub fn sigmoid(&self) -> Self {
+        self.map(|x| 1.0 / (1.0 + (-x).exp()))
+    }
+
+    /// Applies the hyperbolic tangent to each element.
+    ///
+    /// # Returns
+    ///
+    /// A new lazily evaluated ZTensor with the same ranges
+    pub fn tanh(&self) -> Self {
+        self.map(|x| x.tanh())
+    }
+}
+
+#[test]
+fn test_sigmoid_tanh(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZTensor::<1, f32>::from_ranges_values(&[MOmega..POmega], |&[i]| i as f32);
+    assert_eq!(t.sigmoid().get_single_elem(&[0]), 0.5);
+    assert_eq!(t.tanh().get_single_elem(&[0]), 0.0);
+    assert!((t.sigmoid().get_single_elem(&[2]) - 1.0 / (1.0 + (-2.0f32).exp())).abs() < 1e-6);
+    assert!((t.tanh().get_single_elem(&[-1]) - (-1.0f32).tanh()).abs() < 1e-6);
+}
+
+#[test]
+fn test_anti_diagonal(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(0)..Integer(3), Integer(0)..Integer(3)], |&[i, j]|{
+        Elem::new((i * 3 + j) as f32, 0.0)
+    });
+    let main = t.anti_diagonal(2);
+    assert_eq!(main.get_index_ranges(), [Integer(0)..Integer(3)]);
+    assert_eq!(main.get_single_elem(&[0]), Elem::new(2.0, 0.0));
+    assert_eq!(main.get_single_elem(&[1]), Elem::new(4.0, 0.0));
+    assert_eq!(main.get_single_elem(&[2]), Elem::new(6.0, 0.0));
+
+    let shorter = t.anti_diagonal(3);
+    assert_eq!(shorter.get_index_ranges(), [Integer(1)..Integer(3)]);
+    assert_eq!(shorter.get_single_elem(&[1]), Elem::new(5.0, 0.0));
+
+    let infinite = ZMatrix::from_ranges_values(&[Integer(0)..POmega, MOmega..POmega], |&[i, j]|{Elem::new(i as f32, j as f32)});
+    let diag = infinite.anti_diagonal(5);
+    assert_eq!(diag.get_index_ranges(), [Integer(0)..POmega]);
+    assert_eq!(diag.get_single_elem(&[7]), Elem::new(7.0, -2.0));
+}
+
+#[test]
+fn test_permutation_matrix(){
+    use super::omega_int::OmegaInt::*;
+    let perm = [2, 0, 3, 1];
+    let p = ZMatrix::permutation_matrix(&perm);
+    assert_eq!(p.get_index_ranges(), [Integer(0)..Integer(4), Integer(0)..Integer(4)]);
+    let x = ZVector::from_ranges_values(&[Integer(0)..Integer(4)], |&[i]|{Elem::new((10 * i) as f32, 1.0)});
+    for i in 0..4 {
+        let y: Elem = (0..4).map(|j| p.get_single_elem(&[i, j]) * x.get_single_elem(&[j])).sum();
+        assert_eq!(y, x.get_single_elem(&[perm[i as usize] as FiniteIndex]));
+    }
+}
+
+#[test]
+#[should_panic(expected = "permutation_matrix requires a permutation")]
+fn test_permutation_matrix_rejects_repeats(){
+    ZMatrix::permutation_matrix(&[0, 2, 2]);
+}
+
+#[test]
+fn test_scale_rows_cols(){
+    use super::omega_int::OmegaInt::*;
+    let a = ZMatrix::from_ranges_values(&[Integer(0)..Integer(3), Integer(0)..Integer(2)], |&[i, j]|{
+        Elem::new((i + 1) as f32, j as f32)
+    });
+    let rows = ZVector::from_ranges_values(&[MOmega..POmega], |&[i]|{Elem::new(2.0, i as f32)});
+    let cols = ZVector::from_ranges_values(&[Integer(1)..Integer(5)], |&[j]|{Elem::new(j as f32, 0.0)});
+    let scaled = a.scale_rows_cols(&rows, &cols);
+    assert_eq!(scaled.get_index_ranges(), [Integer(0)..Integer(3), Integer(1)..Integer(2)]);
+
+    // Compare with the explicit product diag(rows) * a * diag(cols)
+    let diag = |v: &ZVector, i: FiniteIndex, j: FiniteIndex| if i == j { v.get_single_elem(&[i]) } else { Elem::zero() };
+    for i in 0..3 {
+        for j in 1..2 {
+            let mut expected = Elem::zero();
+            for k in 0..3 {
+                for l in 1..2 {
+                    expected += diag(&rows, i, k) * a.get_single_elem(&[k, l]) * diag(&cols, l, j);
+                }
+            }
+            assert_eq!(scaled.get_single_elem(&[i, j]), expected);
+        }
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Multiplies each element by the conjugate of the element `lag` steps further along an axis.
+    ///
+    /// Element `idx` of the result is `self[idx] * conj(self[idx + lag * e_axis])`,
+    /// the per-element term of an autocorrelation at the given lag. The range
+    /// along `axis` shrinks to the positions where both elements exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `lag` - Offset of the conjugated element along `axis`, which may be negative
+    /// * `axis` - The dimension along which the shift is applied
+    ///
+    /// # Returns
+    ///
+    /// A new lazily evaluated ZTensor
+    pub fn shifted_product(&self, lag: FiniteIndex, axis: usize) -> Self {
+        let mut ranges = self.index_ranges.clone();
+        let r = &self.index_ranges[axis];
+        let lag_omega = OmegaIndex::Integer(lag);
+        ranges[axis] = range_intersection(r, &((r.start - lag_omega)..(r.end - lag_omega)));
+        let valget = self.value_getter.clone();
+        Self::from_ranges_values(&ranges, move |idx: &[FiniteIndex; N]|{
+            let mut shifted = *idx;
+            shifted[axis] += lag;
+            valget(idx) * valget(&shifted).conj()
+        })
+    }
+}
+
+#[test]
+fn test_shifted_product(){
+    use super::omega_int::OmegaInt::*;
+    let data = [Elem::new(1.0, 0.0), Elem::new(0.0, 1.0), Elem::new(2.0, -1.0), Elem::new(3.0, 0.0)];
+    let v = ZVector::from_ranges_values(&[Integer(0)..Integer(4)], move |&[i]|{data[i as usize]});
+    let p = v.shifted_product(1, 0);
+    assert_eq!(p.get_index_ranges(), [Integer(0)..Integer(3)]);
+    for i in 0..3 {
+        assert_eq!(p.get_single_elem(&[i]), data[i as usize] * data[i as usize + 1].conj());
+    }
+    assert_eq!(p.get_single_elem(&[0]), Elem::new(0.0, -1.0));
+
+    let back = v.shifted_product(-2, 0);
+    assert_eq!(back.get_index_ranges(), [Integer(2)..Integer(4)]);
+    assert_eq!(back.get_single_elem(&[3]), data[3] * data[1].conj());
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Creates a sparse tensor from an explicit list of entries.
+    ///
+    /// The entries are stored in a hash map, so lookups take constant time
+    /// regardless of the ranges, which may be infinite. Later entries
+    /// override earlier ones with the same index.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - Array of ranges for each dimension
+    /// * `entries` - The indices and values of the stored elements
+    /// * `default` - The value of every element not listed in `entries`
+    ///
+    /// # Returns
+    ///
+    /// A new ZTensor backed by the entries
+    pub fn from_sparse(ranges: [Range<OmegaIndex>; N], entries: Vec<([FiniteIndex; N], Elem)>, default: Elem) -> Self {
+        let entries: Rc<HashMap<[FiniteIndex; N], Elem>> = Rc::new(entries.into_iter().collect());
+        Self::from_ranges_values(&ranges, move |idx: &[FiniteIndex; N]|{
+            entries.get(idx).copied().unwrap_or(default)
+        }).with_backing(BackingKind::Buffer)
+    }
+}
+
+#[test]
+fn test_from_sparse(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_sparse(
+        [MOmega..POmega, Integer(0)..POmega],
+        vec![([0, 0], Elem::new(1.0, 0.0)), ([-5, 3], Elem::new(0.0, 2.0)), ([1000, 7], Elem::new(-3.0, 0.0))],
+        Elem::new(0.5, 0.0)
+    );
+    assert_eq!(t.get_index_ranges(), [MOmega..POmega, Integer(0)..POmega]);
+    assert_eq!(t.get_single_elem(&[0, 0]), Elem::new(1.0, 0.0));
+    assert_eq!(t.get_single_elem(&[-5, 3]), Elem::new(0.0, 2.0));
+    assert_eq!(t.get_single_elem(&[1000, 7]), Elem::new(-3.0, 0.0));
+    assert_eq!(t.get_single_elem(&[3, -5]), Elem::new(0.5, 0.0));
+}
+
+#[test]
+fn test_partial_trace_subsystem(){
+    use super::omega_int::OmegaInt::*;
+    // A is 2x2 and B is 3x3, the product operator A (x) B is 6x6
+    let a = |i: FiniteIndex, j: FiniteIndex| Elem::new((1 + i + 2 * j) as f32, (i - j) as f32);
+    let b = |i: FiniteIndex, j: FiniteIndex| Elem::new(if i == j { (i + 1) as f32 } else { 0.5 }, 0.0);
+    let product = ZMatrix::from_ranges_values(&[Integer(10)..Integer(16), Integer(-3)..Integer(3)], move |&[r, c]|{
+        let (r, c) = (r - 10, c + 3);
+        a(r / 3, c / 3) * b(r % 3, c % 3)
+    });
+    let trace_a: Elem = (0..2).map(|i| a(i, i)).sum();
+    let trace_b: Elem = (0..3).map(|i| b(i, i)).sum();
+
+    let reduced_a = product.partial_trace_subsystem((2, 3), 0);
+    assert_eq!(reduced_a.get_index_ranges(), [Integer(0)..Integer(2), Integer(0)..Integer(2)]);
+    for i in 0..2 {
+        for j in 0..2 {
+            assert_eq!(reduced_a.get_single_elem(&[i, j]), a(i, j) * trace_b);
+        }
+    }
+    let reduced_b = product.partial_trace_subsystem((2, 3), 1);
+    assert_eq!(reduced_b.get_index_ranges(), [Integer(0)..Integer(3), Integer(0)..Integer(3)]);
+    for i in 0..3 {
+        for j in 0..3 {
+            assert_eq!(reduced_b.get_single_elem(&[i, j]), trace_a * b(i, j));
+        }
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Extends one finite dimension periodically to the whole integer line.
+    ///
+    /// The range of `axis` becomes `MOmega..POmega`, and index `i` along it
+    /// reads the original element at `start + (i - start).rem_euclid(len)`,
+    /// so the original values repeat forever in both directions. The
+    /// dimension must be finite and non-empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - The dimension to tile
+    ///
+    /// # Returns
+    ///
+    /// A new lazily evaluated ZTensor that is periodic along `axis`
+    pub fn tile_infinite(&self, axis: usize) -> Self {
+        let (start, len) = match (self.index_ranges[axis].start, self.index_ranges[axis].end) {
+            (OmegaIndex::Integer(start), OmegaIndex::Integer(end)) if end > start => (start, end - start),
+            _ => panic!("tile_infinite requires the tiled dimension to be finite and non-empty")
+        };
+        let mut ranges = self.index_ranges.clone();
+        ranges[axis] = OmegaIndex::MOmega..OmegaIndex::POmega;
+        let valget = self.value_getter.clone();
+        Self::from_ranges_values(&ranges, move |idx: &[FiniteIndex; N]|{
+            let mut wrapped = *idx;
+            wrapped[axis] = start + (idx[axis] - start).rem_euclid(len);
+            valget(&wrapped)
+        })
+    }
+}
+
+#[test]
+fn test_tile_infinite(){
+    use super::omega_int::OmegaInt::*;
+    let v = ZVector::from_ranges_values(&[Integer(0)..Integer(3)], |&[i]|{Elem::new(i as f32, 0.0)});
+    let tiled = v.tile_infinite(0);
+    assert_eq!(tiled.get_index_ranges(), [MOmega..POmega]);
+    for i in [-1000, -301, -4, -1, 0, 5, 99, 1_000_001] {
+        assert_eq!(tiled.get_single_elem(&[i]), Elem::new(i.rem_euclid(3) as f32, 0.0));
+        assert_eq!(tiled.get_single_elem(&[i]), tiled.get_single_elem(&[i + 3]));
+    }
+
+    // Tiling keeps the original values at their original positions
+    let m = ZMatrix::from_ranges_values(&[Integer(5)..Integer(7), Integer(0)..Integer(2)], |&[i, j]|{Elem::new(i as f32, j as f32)});
+    let tiled = m.tile_infinite(0);
+    assert_eq!(tiled.get_index_ranges(), [MOmega..POmega, Integer(0)..Integer(2)]);
+    assert_eq!(tiled.get_single_elem(&[6, 1]), Elem::new(6.0, 1.0));
+    assert_eq!(tiled.get_single_elem(&[-2, 1]), Elem::new(6.0, 1.0));
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Rotates each element by a per-element phase.
+    ///
+    /// Element `idx` of the result is `self[idx] * exp(i * phase[idx])`. The
+    /// ranges are the intersection of the ranges of this tensor and `phase`.
+    ///
+    /// # Arguments
+    ///
+    /// * `phase` - Real tensor of phase angles in radians
+    ///
+    /// # Returns
+    ///
+    /// A new lazily evaluated ZTensor
+    pub fn rotate_phase<P>(&self, phase: &P) -> Self
+    where P: ZTensorLike<N, DType = f32> + Clone + 'static {
+        let phase_ranges = phase.get_index_ranges();
+        let ranges: [Range<OmegaIndex>; N] = std::array::from_fn(|d| range_intersection(&self.index_ranges[d], &phase_ranges[d]));
+        let valget = self.value_getter.clone();
+        let phase = phase.clone();
+        Self::from_ranges_values(&ranges, move |idx: &[FiniteIndex; N]|{
+            valget(idx) * Elem::cis(phase.get_single_elem(idx))
+        })
+    }
+}
+
+#[test]
+fn test_rotate_phase(){
+    use super::omega_int::OmegaInt::*;
+    let quarter_turn = ZTensor::<2, f32>::from_ranges_values(&[MOmega..POmega, Integer(0)..Integer(2)], |_| std::f32::consts::FRAC_PI_2);
+    let t = ZMatrix::from_ranges_values(&[Integer(0)..Integer(3), Integer(0)..Integer(3)], |&[i, j]|{
+        Elem::new((i * 3 + j) as f32, 0.0)
+    });
+    let rotated = t.rotate_phase(&quarter_turn);
+    assert_eq!(rotated.get_index_ranges(), [Integer(0)..Integer(3), Integer(0)..Integer(2)]);
+    for i in 0..3 {
+        for j in 0..2 {
+            let expected = t.get_single_elem(&[i, j]) * Elem::i();
+            assert!((rotated.get_single_elem(&[i, j]) - expected).norm() < 1e-5);
+        }
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Computes the weighted squared norm `sum weights[idx] * |self[idx]|^2`.
+    ///
+    /// The sum runs over the intersection of the ranges of this tensor and
+    /// `weights`, which must be finite. With unit weights this is the squared
+    /// Frobenius norm.
+    ///
+    /// # Arguments
+    ///
+    /// * `weights` - Real weight for each element
+    ///
+    /// # Returns
+    ///
+    /// The weighted sum of squared magnitudes
+    pub fn weighted_norm_sqr<W: ZTensorLike<N, DType = f32>>(&self, weights: &W) -> f32 {
+        let weight_ranges = weights.get_index_ranges();
+        let ranges: [Range<OmegaIndex>; N] = std::array::from_fn(|d| range_intersection(&self.index_ranges[d], &weight_ranges[d]));
+        row_major_indices(finite_ranges(&ranges))
+            .map(|idx| weights.get_single_elem(&idx) * self.get_single_elem(&idx).norm_sqr())
+            .sum()
+    }
+}
+
+#[test]
+fn test_weighted_norm_sqr(){
+    use super::omega_int::OmegaInt::*;
+    let everywhere = [MOmega..POmega, MOmega..POmega];
+    let t = ZMatrix::from_ranges_values(&[Integer(0)..Integer(2), Integer(0)..Integer(3)], |&[i, j]|{
+        Elem::new(i as f32, (j - 1) as f32)
+    });
+    assert_eq!(t.weighted_norm_sqr(&ZTensor::<2, f32>::ones(&everywhere)), t.full_inner(&t).re);
+
+    // |t|^2 is [[1, 0, 1], [2, 1, 2]] and the weights are [[0, 1, 2], [1, 2, 3]]
+    let weighted = t.weighted_norm_sqr(&ZTensor::<2, f32>::from_ranges_values(&everywhere, |&[i, j]| (i + j) as f32));
+    assert_eq!(weighted, 0.0 + 0.0 + 2.0 + 2.0 + 2.0 + 6.0);
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Checks whether two tensors agree over a finite window.
+    ///
+    /// The declared ranges of both tensors are ignored, so this can compare
+    /// infinite tensors sampled over a region. Two elements agree when the
+    /// magnitude of their difference is at most `tol`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The tensor to compare against
+    /// * `window` - Finite ranges of indices to compare
+    /// * `tol` - Largest allowed magnitude of the difference
+    ///
+    /// # Returns
+    ///
+    /// True if every pair of elements in the window agrees
+    pub fn values_eq_over(&self, other: &Self, window: &[Range<FiniteIndex>; N], tol: f32) -> bool {
+        row_major_indices(window.clone())
+            .all(|idx| (self.get_single_elem(&idx) - other.get_single_elem(&idx)).norm() <= tol)
+    }
+}
+
+#[test]
+fn test_values_eq_over(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[MOmega..POmega, MOmega..POmega], |&[i, j]|{
+        Elem::new((i % 4) as f32, (j % 3) as f32)
+    });
+    let window = [-10..10, -5..7];
+    assert!(t.values_eq_over(&t.conj_trans().conj_trans(), &window, 0.0));
+
+    // Tiling a single period of a periodic tensor reproduces it
+    let period = t.get_slice(&[Integer(0)..Integer(4), MOmega..POmega]).tile_infinite(0);
+    assert!(t.values_eq_over(&period, &[0..12, -5..7], 1e-6));
+    assert!(!t.values_eq_over(&period, &window, 1e-6));
+    assert!(!t.values_eq_over(&t.conj_trans(), &window, 1e-6));
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Splits the tensor into magnitude and phase tensors.
+    ///
+    /// Both results have the same ranges as this tensor. The phase is in
+    /// radians, in `(-pi, pi]`.
+    ///
+    /// # Returns
+    ///
+    /// The lazily evaluated real magnitude and phase tensors
+    pub fn to_polar(&self) -> (ZTensor<N, f32>, ZTensor<N, f32>) {
+        (self.map(|z| z.norm()), self.map(|z| z.arg()))
+    }
+
+    /// Creates a tensor from magnitude and phase tensors.
+    ///
+    /// This is the inverse of `to_polar`. The ranges are the intersection
+    /// of the ranges of `mag` and `phase`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mag` - Magnitude of each element
+    /// * `phase` - Phase of each element in radians
+    ///
+    /// # Returns
+    ///
+    /// A new lazily evaluated ZTensor with elements `mag * exp(i * phase)`
+    pub fn from_polar(mag: &ZTensor<N, f32>, phase: &ZTensor<N, f32>) -> Self {
+        let ranges: [Range<OmegaIndex>; N] = std::array::from_fn(|d| range_intersection(&mag.index_ranges[d], &phase.index_ranges[d]));
+        let mag_getter = mag.value_getter.clone();
+        let phase_getter = phase.value_getter.clone();
+        Self::from_ranges_values(&ranges, move |idx: &[FiniteIndex; N]|{
+            Elem::from_polar(mag_getter(idx), phase_getter(idx))
+        })
+    }
+}
+
+#[test]
+fn test_polar_round_trip(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(-2)..Integer(3), Integer(-2)..Integer(3)], |&[i, j]|{
+        Elem::new(i as f32, j as f32)
+    });
+    let (mag, phase) = t.to_polar();
+    assert_eq!(mag.get_single_elem(&[-2, 0]), 2.0);
+    assert_eq!(phase.get_single_elem(&[0, 1]), std::f32::consts::FRAC_PI_2);
+    assert!(mag.to_complex().values_eq_over(&t.map(|z| Elem::new(z.norm(), 0.0)), &[-2..3, -2..3], 0.0));
+    let back = ZMatrix::from_polar(&mag, &phase);
+    assert_eq!(back.get_index_ranges(), t.get_index_ranges());
+    assert!(back.values_eq_over(&t, &[-2..3, -2..3], 1e-5));
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Computes a moving average along one dimension.
+    ///
+    /// Output element `i` along `axis` is the mean of the `window` input
+    /// elements at positions `i..i + window`. Edges are handled by shrinking
+    /// rather than padding: the range along `axis` loses its last
+    /// `window - 1` positions, so every output averages a full window. An
+    /// axis shorter than the window gives an empty range. Infinite ends stay
+    /// infinite.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - The dimension to average along
+    /// * `window` - Number of consecutive elements averaged, must be positive
+    ///
+    /// # Returns
+    ///
+    /// A new lazily evaluated ZTensor
+    pub fn moving_average(&self, axis: usize, window: usize) -> Self {
+        assert!(window > 0, "moving_average requires a positive window");
+        let mut ranges = self.index_ranges.clone();
+        let r = &self.index_ranges[axis];
+        let shrunk_end = r.end - OmegaIndex::Integer(window as FiniteIndex - 1);
+        ranges[axis] = range_intersection(r, &(r.start..shrunk_end));
+        let valget = self.value_getter.clone();
+        Self::from_ranges_values(&ranges, move |idx: &[FiniteIndex; N]|{
+            let mut shifted = *idx;
+            let sum: Elem = (0..window as FiniteIndex).map(|k| {
+                shifted[axis] = idx[axis] + k;
+                valget(&shifted)
+            }).sum();
+            sum / window as f32
+        })
+    }
+}
+
+#[test]
+fn test_moving_average(){
+    use super::omega_int::OmegaInt::*;
+    let data = [1.0, 4.0, 2.0, 8.0, 5.0, 7.0];
+    let v = ZVector::from_ranges_values(&[Integer(0)..Integer(6)], move |&[i]|{Elem::new(data[i as usize], 0.0)});
+    let avg = v.moving_average(0, 3);
+    assert_eq!(avg.get_index_ranges(), [Integer(0)..Integer(4)]);
+    for i in 0..4 {
+        let expected = (data[i] + data[i + 1] + data[i + 2]) / 3.0;
+        assert!((avg.get_single_elem(&[i as FiniteIndex]) - Elem::new(expected, 0.0)).norm() < 1e-6);
+    }
+    let too_long = v.moving_average(0, 10);
+    assert_eq!(too_long.get_index_ranges(), [Integer(0)..Integer(0)]);
+}
+
+#[test]
+fn test_dft_matrix(){
+    use super::omega_int::OmegaInt::*;
+    let f = ZMatrix::dft_matrix(4);
+    assert_eq!(f.get_index_ranges(), [Integer(0)..Integer(4), Integer(0)..Integer(4)]);
+    assert!((f.get_single_elem(&[1, 1]) - Elem::new(0.0, -0.5)).norm() < 1e-6);
+    let fh = f.conj_trans();
+    for i in 0..4 {
+        for j in 0..4 {
+            let product: Elem = (0..4).map(|k| fh.get_single_elem(&[i, k]) * f.get_single_elem(&[k, j])).sum();
+            let expected = if i == j { Elem::one() } else { Elem::zero() };
+            assert!((product - expected).norm() < 1e-5);
+        }
+    }
+}
+
+/// Trait for lifting real-valued tensor-like objects to complex ZTensors.
+pub trait ToComplex<const N: usize> : ZTensorLike<N, DType = f32> {
+    /// Converts the tensor to a complex ZTensor with zero imaginary parts.
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZTensor with the same ranges
+    fn to_complex(&self) -> ZTensor<N>;
+}
+
+/// Blanket implementation of ToComplex for all cloneable real-valued tensor-like types.
+impl<const N: usize, T: ZTensorLike<N, DType = f32> + Clone + 'static> ToComplex<N> for T {
+    fn to_complex(&self) -> ZTensor<N> {
+        let src = self.clone();
+        ZTensor::from_ranges_values(&self.get_index_ranges(), move |idx: &[FiniteIndex; N]|{
+            Elem::new(src.get_single_elem(idx), 0.0)
+        })
+    }
+}
+
+#[test]
+fn test_to_complex(){
+    use super::omega_int::OmegaInt::*;
+    let ramp = ZTensor::<1, f32>::from_ranges_values(&[Integer(-4)..POmega], |&[i]| i as f32 * 0.5);
+    let lifted = ramp.to_complex();
+    assert_eq!(lifted.get_index_ranges(), [Integer(-4)..POmega]);
+    for i in -4..10 {
+        let value = lifted.get_single_elem(&[i]);
+        assert_eq!(value.re, ramp.get_single_elem(&[i]));
+        assert_eq!(value.im, 0.0);
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Creates a separable tensor from a table of values per axis.
+    ///
+    /// The element at an index is obtained by looking up the value of each
+    /// axis in its table, relative to the start of the range of that axis,
+    /// and combining them. Only the tables are stored, so outer sums and
+    /// outer products take memory linear in the lengths of the axes.
+    /// Panics if a range is infinite or longer than its table.
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - Values along each axis, starting at the start of its range
+    /// * `ranges` - Array of ranges for each dimension
+    /// * `combine` - Function combining the per-axis values into an element
+    ///
+    /// # Returns
+    ///
+    /// A new ZTensor backed by the tables
+    pub fn from_separable(tables: [Rc<Vec<Elem>>; N], ranges: [Range<OmegaIndex>; N], combine: impl Fn(&[Elem; N]) -> Elem + Clone + 'static) -> Self {
+        let finite = finite_ranges(&ranges);
+        for (axis, r) in finite.iter().enumerate() {
+            assert!((r.end - r.start).max(0) as usize <= tables[axis].len(), "Table of axis {} is shorter than the dimension", axis);
+        }
+        let starts = finite.map(|r| r.start);
+        Self::from_ranges_values(&ranges, move |idx: &[FiniteIndex; N]|{
+            let values: [Elem; N] = std::array::from_fn(|axis| tables[axis][(idx[axis] - starts[axis]) as usize]);
+            combine(&values)
+        })
+    }
+}
+
+#[test]
+fn test_from_separable(){
+    use super::omega_int::OmegaInt::*;
+    let rows = Rc::new(vec![Elem::new(1.0, 0.0), Elem::new(2.0, 1.0), Elem::new(-3.0, 0.0)]);
+    let cols = Rc::new(vec![Elem::new(0.5, 0.0), Elem::new(0.0, -1.0)]);
+    let ranges = [Integer(-1)..Integer(2), Integer(4)..Integer(6)];
+
+    let outer_sum = ZMatrix::from_separable([rows.clone(), cols.clone()], ranges.clone(), |&[a, b]| a + b);
+    let outer_product = ZMatrix::from_separable([rows.clone(), cols.clone()], ranges.clone(), |&[a, b]| a * b);
+    assert_eq!(outer_sum.get_index_ranges(), ranges);
+    for i in 0..3 {
+        for j in 0..2 {
+            let idx = [i as FiniteIndex - 1, j as FiniteIndex + 4];
+            assert_eq!(outer_sum.get_single_elem(&idx), rows[i] + cols[j]);
+            assert_eq!(outer_product.get_single_elem(&idx), rows[i] * cols[j]);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "Table of axis 1 is shorter than the dimension")]
+fn test_from_separable_short_table(){
+    use super::omega_int::OmegaInt::*;
+    let table = Rc::new(vec![Elem::one(); 3]);
+    let _ = ZMatrix::from_separable([table.clone(), table], [Integer(0)..Integer(3), Integer(0)..Integer(4)], |&[a, b]| a * b);
+}
+
+#[test]
+#[should_panic(expected = "ZTensor has an infinite dimension where a finite one is required")]
+fn test_from_separable_infinite_range(){
+    use super::omega_int::OmegaInt::*;
+    let table = Rc::new(vec![Elem::one(); 3]);
+    let _ = ZVector::from_separable([table], [Integer(0)..POmega], |&[a]| a);
+}
+
+#[test]
+fn test_companion_matrix(){
+    use super::omega_int::OmegaInt::*;
+    // x^3 - 2x^2 + 5
+    let coeffs = ZVector::from_ranges_values(&[Integer(3)..Integer(6)], |&[i]| Elem::new([5.0, 0.0, -2.0][(i - 3) as usize], 0.0));
+    let c = ZMatrix::companion_matrix(&coeffs);
+    assert_eq!(c.get_index_ranges(), [Integer(0)..Integer(3), Integer(0)..Integer(3)]);
+    let expected = [[0.0, 0.0, -5.0], [1.0, 0.0, 0.0], [0.0, 1.0, 2.0]];
+    for i in 0..3 {
+        for j in 0..3 {
+            assert_eq!(c.get_single_elem(&[i, j]), Elem::new(expected[i as usize][j as usize], 0.0));
+        }
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Applies a separable window to a finite tensor.
+    ///
+    /// Each element is multiplied by the product of the window values of
+    /// its indices, looked up relative to the start of the range of each
+    /// axis. This applies window functions such as Hann or Hamming along
+    /// every axis. Panics if a dimension is infinite or longer than its window.
+    ///
+    /// # Arguments
+    ///
+    /// * `windows` - Window values along each axis, starting at the start of its range
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZTensor with the same ranges
+    pub fn apodize(&self, windows: [Rc<Vec<f32>>; N]) -> Self {
+        let ranges = self.finite_index_ranges();
+        for (axis, r) in ranges.iter().enumerate() {
+            assert!((r.end - r.start).max(0) as usize <= windows[axis].len(), "Window of axis {} is shorter than the dimension", axis);
+        }
+        let starts = ranges.map(|r| r.start);
+        let valget = self.value_getter.clone();
+        Self::from_ranges_values(&self.index_ranges, move |idx: &[FiniteIndex; N]|{
+            let weight: f32 = (0..N).map(|axis| windows[axis][(idx[axis] - starts[axis]) as usize]).product();
+            valget(idx) * weight
+        })
+    }
+}
+
+#[test]
+fn test_apodize(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(-2)..Integer(3), Integer(10)..Integer(15)], |_| Elem::new(2.0, -2.0));
+    let ramp = Rc::new(vec![0.25, 0.5, 1.0, 0.5, 0.25]);
+    let a = t.apodize([ramp.clone(), ramp]);
+    assert_eq!(a.get_index_ranges(), t.get_index_ranges());
+    // The center is unchanged and the corners are attenuated the most
+    assert_eq!(a.get_single_elem(&[0, 12]), Elem::new(2.0, -2.0));
+    assert_eq!(a.get_single_elem(&[-2, 10]), Elem::new(0.125, -0.125));
+    assert_eq!(a.get_single_elem(&[2, 14]), Elem::new(0.125, -0.125));
+    assert_eq!(a.get_single_elem(&[1, 12]), Elem::new(1.0, -1.0));
+    // Reversed ranges are empty and need no window values
+    let empty = ZMatrix::from_ranges_values(&[Integer(3)..Integer(1), Integer(0)..Integer(2)], |_| Elem::new(0.0, 0.0));
+    assert_eq!(empty.apodize([Rc::new(vec![]), Rc::new(vec![1.0, 1.0])]).get_index_ranges(), empty.get_index_ranges());
+}
+
+impl ZVector {
+    /// Interleaves two finite vectors element by element.
+    ///
+    /// Element `2k` of the result is `even[k]` and element `2k + 1` is
+    /// `odd[k]`, where `k` counts from the start of the range of each input.
+    /// The result ranges over `0..2m` for `m` the length of the shorter input.
+    /// Panics if either input is infinite.
+    ///
+    /// # Arguments
+    ///
+    /// * `even` - Vector supplying the even elements
+    /// * `odd` - Vector supplying the odd elements
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated interleaved ZVector
+    pub fn interleave(even: &ZVector, odd: &ZVector) -> ZVector {
+        let ([even_range], [odd_range]) = (even.finite_index_ranges(), odd.finite_index_ranges());
+        let len = (even_range.end - even_range.start).min(odd_range.end - odd_range.start).max(0);
+        let (even_valget, odd_valget) = (even.value_getter.clone(), odd.value_getter.clone());
+        ZVector::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(2 * len)], move |&[i]|{
+            if i % 2 == 0 {
+                even_valget(&[even_range.start + i / 2])
+            } else {
+                odd_valget(&[odd_range.start + i / 2])
+            }
+        })
+    }
+}
+
+#[test]
+fn test_interleave(){
+    use super::omega_int::OmegaInt::*;
+    let even = ZVector::from_ranges_values(&[Integer(0)..Integer(3)], |&[i]| Elem::new((2 * i) as f32, 0.0));
+    let odd = ZVector::from_ranges_values(&[Integer(-1)..Integer(3)], |&[i]| Elem::new((2 * i + 3) as f32, 0.0));
+    let v = ZVector::interleave(&even, &odd);
+    assert_eq!(v.get_index_ranges(), [Integer(0)..Integer(6)]);
+    for i in 0..6 {
+        assert_eq!(v.get_single_elem(&[i]), Elem::new(i as f32, 0.0));
+    }
+}
+
+impl ZMatrix {
+    /// Computes the outer difference of two vectors.
+    ///
+    /// Element `[i, j]` is `a[i] - b[j]`, over the range of `a` in the first
+    /// dimension and the range of `b` in the second. Both ranges may be infinite.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Vector indexed by the rows
+    /// * `b` - Vector indexed by the columns
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZMatrix of differences
+    pub fn outer_difference(a: &ZVector, b: &ZVector) -> ZMatrix {
+        let (a_valget, b_valget) = (a.value_getter.clone(), b.value_getter.clone());
+        ZMatrix::from_ranges_values(&[a.index_ranges[0].clone(), b.index_ranges[0].clone()], move |&[i, j]|{
+            a_valget(&[i]) - b_valget(&[j])
+        })
+    }
+}
+
+#[test]
+fn test_outer_difference(){
+    use super::omega_int::OmegaInt::*;
+    let a = ZVector::from_ranges_values(&[Integer(0)..Integer(3)], |&[i]| Elem::new(i as f32, 1.0));
+    let b = ZVector::from_ranges_values(&[Integer(-2)..Integer(2)], |&[j]| Elem::new(0.0, j as f32));
+    let d = ZMatrix::outer_difference(&a, &b);
+    assert_eq!(d.get_index_ranges(), [Integer(0)..Integer(3), Integer(-2)..Integer(2)]);
+    assert_eq!(d.get_single_elem(&[0, 0]), Elem::new(0.0, 1.0));
+    assert_eq!(d.get_single_elem(&[2, -2]), Elem::new(2.0, 3.0));
+    assert_eq!(d.get_single_elem(&[1, 1]), Elem::new(1.0, 0.0));
+    assert_eq!(d.get_single_elem(&[2, 1]).norm_sqr(), 4.0);
+}
+
+impl ZMatrix {
+    /// Approximates the matrix exponential by a truncated power series.
+    ///
+    /// The result is `sum(A^k / k!)` for `k` in `0..terms`, over the same
+    /// ranges as this matrix. The series is summed once, on the first
+    /// element access, and shared by all clones of the result. The error
+    /// of the truncation is roughly `|A|^terms / terms!`, so matrices with
+    /// a small norm need few terms, while larger norms need many more and
+    /// suffer from cancellation in f32. The series is exact for nilpotent
+    /// matrices once `terms` exceeds the nilpotency index. Panics if the
+    /// matrix is not square or has an infinite dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `terms` - Number of series terms to sum
+    ///
+    /// # Returns
+    ///
+    /// A ZMatrix approximating `exp(A)`
+    pub fn expm_series(&self, terms: usize) -> ZMatrix {
+        assert!(self.index_ranges[0] == self.index_ranges[1], "expm_series requires a square matrix");
+        let range = self.finite_index_ranges()[0].clone();
+        let start = range.start;
+        let n = (range.end - range.start).max(0) as usize;
+        let valget = self.value_getter.clone();
+        let sum: Rc<std::cell::OnceCell<Vec<Elem>>> = Rc::new(std::cell::OnceCell::new());
+        ZMatrix::from_ranges_values(&self.index_ranges, move |&[i, j]|{
+            let sum = sum.get_or_init(|| {
+                let a: Vec<Elem> = row_major_indices([range.clone(), range.clone()]).map(|idx| valget(&idx)).collect();
+                // The current term A^k / k!, starting from the identity
+                let mut term: Vec<Elem> = ZMatrix::identity(n).to_vec().0;
+                let mut sum = vec![Elem::zero(); n * n];
+                for k in 0..terms {
+                    for (s, t) in sum.iter_mut().zip(term.iter()) {
+                        *s += t;
+                    }
+                    let scale = 1.0 / (k + 1) as f32;
+                    term = (0..n * n).map(|p| {
+                        let (r, c) = (p / n, p % n);
+                        (0..n).map(|q| term[r * n + q] * a[q * n + c]).sum::<Elem>() * scale
+                    }).collect();
+                }
+                sum
+            });
+            sum[(i - start) as usize * n + (j - start) as usize]
+        }).with_backing(BackingKind::Memoized)
+    }
+}
+
+#[test]
+fn test_expm_series(){
+    use super::omega_int::OmegaInt::*;
+    // Strictly upper triangular, so A^3 = 0 and exp(A) = I + A + A^2 / 2
+    let entries = [[0.0, 2.0, 3.0], [0.0, 0.0, 4.0], [0.0, 0.0, 0.0]];
+    let a = ZMatrix::from_ranges_values(&[Integer(1)..Integer(4), Integer(1)..Integer(4)], move |&[i, j]|{
+        Elem::new(entries[(i - 1) as usize][(j - 1) as usize], 0.0)
+    });
+    let expected = [[1.0, 2.0, 7.0], [0.0, 1.0, 4.0], [0.0, 0.0, 1.0]];
+    for terms in [3, 10] {
+        let e = a.expm_series(terms);
+        assert_eq!(e.get_index_ranges(), a.get_index_ranges());
+        for i in 1..4 {
+            for j in 1..4 {
+                assert_eq!(e.get_single_elem(&[i, j]), Elem::new(expected[(i - 1) as usize][(j - 1) as usize], 0.0));
+            }
+        }
+    }
+    assert_eq!(a.expm_series(0).get_single_elem(&[1, 1]), Elem::zero());
+}
+
+impl ZMatrix {
+    /// Creates a matrix whose elements depend on the distance to a center point.
+    ///
+    /// The element at `[i, j]` is `f(d)` where `d` is the Euclidean distance
+    /// from `(i, j)` to `center`. The ranges may be infinite.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - Ranges of the rows and columns
+    /// * `center` - The center point, in row and column coordinates
+    /// * `f` - Function mapping a distance to an element
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated radial ZMatrix
+    pub fn from_radial(ranges: [Range<OmegaIndex>; 2], center: (f64, f64), f: impl Fn(f64) -> Elem + Clone + 'static) -> ZMatrix {
+        ZMatrix::from_ranges_values(&ranges, move |&[i, j]|{
+            f((i as f64 - center.0).hypot(j as f64 - center.1))
+        })
+    }
+}
+
+#[test]
+fn test_from_radial(){
+    use super::omega_int::OmegaInt::*;
+    let ramp = ZMatrix::from_radial([MOmega..POmega, MOmega..POmega], (1.0, -2.0), |d| Elem::new(d as f32, 0.0));
+    let window = ramp.get_slice(&[Integer(-3)..Integer(6), Integer(-6)..Integer(3)]);
+    assert_eq!(window.get_single_elem(&[1, -2]), Elem::zero());
+    assert_eq!(window.get_single_elem(&[4, 2]), Elem::new(5.0, 0.0));
+    for k in 1..4 {
+        assert!(window.get_single_elem(&[1 + k, -2]).re > window.get_single_elem(&[k, -2]).re);
+        assert!(window.get_single_elem(&[1, -2 - k]).re > window.get_single_elem(&[1, -1 - k]).re);
+        assert!(window.get_single_elem(&[1 + k, -2 + k]).re > window.get_single_elem(&[k, -3 + k]).re);
+    }
+}
+
+impl<const N: usize, T: 'static> ZTensor<N, T> {
+    /// Combines two tensors element by element over the intersection of their ranges.
+    ///
+    /// Panics if the ranges are disjoint in some dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The right operand
+    /// * `op` - Function combining an element of this tensor with one of `other`
+    /// * `op_name` - Name of the operation, used in the panic message
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZTensor over the common ranges
+    fn elementwise(&self, other: &Self, op: fn(T, T) -> T, op_name: &str) -> Self {
+        for (axis, r) in Self::common_ranges(&[self, other]).iter().enumerate() {
+            assert!(r.start != r.end, "Cannot {} tensors with disjoint ranges in axis {}", op_name, axis);
+        }
+        self.zip_with(other, op)
+    }
+
+    /// Combines two tensors element by element with a function.
+    ///
+    /// The result is defined over the intersection of the ranges, which is
+    /// empty in the dimensions where they do not overlap.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The second tensor
+    /// * `f` - Function combining an element of this tensor with one of `other`
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZTensor over the common ranges
+    pub fn zip_with<G>(&self, other: &Self, f: G) -> Self where G: Fn(T, T) -> T + Clone + 'static {
+        let (lhs, rhs) = (self.value_getter.clone(), other.value_getter.clone());
+        Self::from_ranges_values(&Self::common_ranges(&[self, other]), move |idx: &[FiniteIndex; N]|{
+            f(lhs(idx), rhs(idx))
+        })
+    }
+}
+
+#[test]
+fn test_zip_with(){
+    use super::omega_int::OmegaInt::*;
+    let a = ZVector::from_ranges_values(&[MOmega..Integer(4)], |&[i]| Elem::new(i as f32, 1.0));
+    let b = ZVector::from_ranges_values(&[Integer(0)..POmega], |&[i]| Elem::new(2.0, i as f32));
+    let sum = a.zip_with(&b, |x, y| x + y);
+    let product = a.zip_with(&b, |x, y| x * y);
+    assert_eq!(sum.get_index_ranges(), [Integer(0)..Integer(4)]);
+    assert_eq!(product.clone().get_index_ranges(), [Integer(0)..Integer(4)]);
+    for i in 0..4 {
+        assert_eq!(sum.get_single_elem(&[i]), (&a + &b).get_single_elem(&[i]));
+        assert_eq!(product.get_single_elem(&[i]), a.hadamard(&b).get_single_elem(&[i]));
+    }
+    assert_eq!(product.get_single_elem(&[3]), Elem::new(3.0, 1.0) * Elem::new(2.0, 3.0));
+    // Disjoint ranges give an empty tensor
+    let c = ZVector::from_ranges_values(&[Integer(10)..Integer(12)], |_| Elem::one());
+    assert_eq!(a.zip_with(&c, |x, _| x).get_index_ranges(), [Integer(10)..Integer(10)]);
+}
+
+/// Element-wise addition over the intersection of the ranges.
+///
+/// Panics if the ranges are disjoint in some dimension.
+impl<const N: usize, T: Add<Output = T> + 'static> Add for &ZTensor<N, T> {
+    type Output = ZTensor<N, T>;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.elementwise(rhs, |a, b| a + b, "add")
+    }
+}
+
+impl<const N: usize, T: Add<Output = T> + 'static> Add for ZTensor<N, T> {
+    type Output = ZTensor<N, T>;
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+/// Element-wise subtraction over the intersection of the ranges.
+///
+/// Panics if the ranges are disjoint in some dimension.
+impl<const N: usize, T: Sub<Output = T> + 'static> Sub for &ZTensor<N, T> {
+    type Output = ZTensor<N, T>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.elementwise(rhs, |a, b| a - b, "subtract")
+    }
+}
+
+impl<const N: usize, T: Sub<Output = T> + 'static> Sub for ZTensor<N, T> {
+    type Output = ZTensor<N, T>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        &self - &rhs
+    }
+}
+
+impl<const N: usize, T: Mul<Output = T> + 'static> ZTensor<N, T> {
+    /// Computes the element-wise (Hadamard) product with another tensor.
+    ///
+    /// The result is defined over the intersection of the ranges.
+    /// Panics if the ranges are disjoint in some dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other factor
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZTensor of element-wise products
+    pub fn hadamard(&self, other: &Self) -> Self {
+        self.elementwise(other, |a, b| a * b, "multiply")
+    }
+}
+
+#[test]
+fn test_add(){
+    use super::omega_int::OmegaInt::*;
+    let a = ZMatrix::from_ranges_values(&[MOmega..Integer(5), Integer(0)..POmega], |&[i, j]| Elem::new(i as f32, j as f32));
+    let b = ZMatrix::from_ranges_values(&[Integer(2)..POmega, MOmega..Integer(3)], |&[i, j]| Elem::new((i * j) as f32, 1.0));
+    let c = &a + &b;
+    assert_eq!(c.get_index_ranges(), [Integer(2)..Integer(5), Integer(0)..Integer(3)]);
+    assert_eq!(c.get_single_elem(&[3, 2]), Elem::new(9.0, 3.0));
+    assert_eq!((a + b).get_single_elem(&[4, 0]), Elem::new(4.0, 1.0));
+}
+
+#[test]
+#[should_panic(expected = "Cannot add tensors with disjoint ranges in axis 1")]
+fn test_add_disjoint(){
+    use super::omega_int::OmegaInt::*;
+    let a = ZMatrix::zeros(&[Integer(0)..Integer(2), Integer(0)..Integer(2)]);
+    let b = ZMatrix::zeros(&[Integer(0)..Integer(2), Integer(5)..Integer(7)]);
+    let _ = a + b;
+}
+
+impl<const N: usize, T: 'static> ZTensor<N, T> {
+    /// Applies a function to every element.
+    ///
+    /// The function may change the element type.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Function mapping an element to the new element
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZTensor with the same ranges
+    pub fn map<U, G>(&self, f: G) -> ZTensor<N, U> where G: Fn(T) -> U + Clone + 'static {
+        let valget = self.value_getter.clone();
+        ZTensor::from_ranges_values(&self.index_ranges, move |idx: &[FiniteIndex; N]|{
+            f(valget(idx))
+        })
+    }
+}
+
+#[test]
+fn test_map(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(0)..Integer(3), MOmega..POmega], |&[i, j]| Elem::new(i as f32, j as f32));
+    let conj = t.map(|z| z.conj());
+    let trans = t.conj_trans();
+    assert_eq!(conj.get_index_ranges(), t.get_index_ranges());
+    let cloned = conj.clone();
+    for (i, j) in [(0, -4), (2, 7), (1, 0)] {
+        assert_eq!(conj.get_single_elem(&[i, j]), trans.get_single_elem(&[j, i]));
+        assert_eq!(cloned.get_single_elem(&[i, j]), Elem::new(i as f32, -j as f32));
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Multiplies every element by a constant.
+    ///
+    /// # Arguments
+    ///
+    /// * `c` - The constant factor
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZTensor with the same ranges
+    pub fn scale(&self, c: Elem) -> Self {
+        self.map(move |x| x * c)
+    }
+
+    /// Adds a constant to every element.
+    ///
+    /// # Arguments
+    ///
+    /// * `c` - The constant term
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZTensor with the same ranges
+    pub fn add_scalar(&self, c: Elem) -> Self {
+        self.map(move |x| x + c)
+    }
+}
+
+/// Multiplication of every element by a constant, equivalent to `scale`.
+impl<const N: usize> Mul<Elem> for &ZTensor<N> {
+    type Output = ZTensor<N>;
+    fn mul(self, rhs: Elem) -> Self::Output {
+        self.scale(rhs)
+    }
+}
+
+impl<const N: usize> Mul<Elem> for ZTensor<N> {
+    type Output = ZTensor<N>;
+    fn mul(self, rhs: Elem) -> Self::Output {
+        self.scale(rhs)
+    }
+}
+
+#[test]
+fn test_scalar_ops(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[MOmega..Integer(3), Integer(-1)..POmega], |&[i, j]| Elem::new(i as f32, j as f32));
+    let c = Elem::new(0.0, 2.0);
+    let scaled = t.scale(c);
+    let shifted = t.add_scalar(c);
+    let product = &t * c;
+    for t2 in [&scaled, &shifted, &product] {
+        assert_eq!(t2.get_index_ranges(), t.get_index_ranges());
+    }
+    for idx in [[-10, 4], [2, -1], [0, 100]] {
+        assert_eq!(scaled.get_single_elem(&idx), t.get_single_elem(&idx) * c);
+        assert_eq!(shifted.get_single_elem(&idx), t.get_single_elem(&idx) + c);
+        assert_eq!(product.get_single_elem(&idx), scaled.get_single_elem(&idx));
+    }
+    assert_eq!((t * Elem::new(2.0, 0.0)).get_single_elem(&[1, 1]), Elem::new(2.0, 2.0));
+}
+
+impl<const N: usize, T: 'static> ZTensor<N, T> {
+    /// Reorders the axes of this tensor.
+    ///
+    /// Axis `d` of the result is axis `order[d]` of this tensor, so the
+    /// element of the result at `idx` is the element of this tensor whose
+    /// index along axis `order[d]` is `idx[d]`. For a matrix, `[1, 0]` gives
+    /// the transpose. Panics if `order` is not a permutation of `0..N`.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The axis of this tensor for each axis of the result
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZTensor with permuted axes
+    pub fn permute_axes(&self, order: [usize; N]) -> Self {
+        let mut seen = [false; N];
+        for &axis in order.iter() {
+            assert!(axis < N && !seen[axis], "permute_axes requires a permutation of the axes");
+            seen[axis] = true;
+        }
+        let ranges: [Range<OmegaIndex>; N] = std::array::from_fn(|d| self.index_ranges[order[d]].clone());
+        let valget = self.value_getter.clone();
+        Self::from_ranges_values(&ranges, move |idx: &[FiniteIndex; N]|{
+            let mut source = [0; N];
+            for d in 0..N {
+                source[order[d]] = idx[d];
+            }
+            valget(&source)
+        })
+    }
+
+    /// Returns the complex conjugate of every element, without transposing.
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZTensor with the same ranges
+    pub fn conj(&self) -> Self where T: Conjugate {
+        self.map(|x| x.conjugate())
+    }
+}
+
+#[test]
+fn test_permute_axes(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(0)..Integer(2), Integer(-1)..Integer(2)], |&[i, j]| Elem::new(i as f32, j as f32));
+    let p = t.permute_axes([1, 0]);
+    assert_eq!(p.get_index_ranges(), [Integer(-1)..Integer(2), Integer(0)..Integer(2)]);
+    for i in 0..2 {
+        for j in -1..2 {
+            assert_eq!(p.get_single_elem(&[j, i]), t.get_single_elem(&[i, j]));
+            assert_eq!(t.conj_trans().get_single_elem(&[j, i]), t.get_single_elem(&[i, j]).conj());
+        }
+    }
+    assert_eq!(t.conj().get_single_elem(&[1, 1]), Elem::new(1.0, -1.0));
+
+    let t3 = ZTensor::<3>::from_ranges_values(&[Integer(0)..Integer(2), Integer(0)..Integer(3), MOmega..POmega], |&[a, b, c]| Elem::new((100 * a + 10 * b) as f32, c as f32));
+    let p3 = t3.permute_axes([2, 0, 1]);
+    assert_eq!(p3.get_index_ranges(), [MOmega..POmega, Integer(0)..Integer(2), Integer(0)..Integer(3)]);
+    assert_eq!(p3.get_single_elem(&[-7, 1, 2]), t3.get_single_elem(&[1, 2, -7]));
+}
+
+#[test]
+#[should_panic(expected = "permute_axes requires a permutation of the axes")]
+fn test_permute_axes_rejects_repeats(){
+    use super::omega_int::OmegaInt::*;
+    let _ = ZMatrix::zeros(&[Integer(0)..Integer(2), Integer(0)..Integer(2)]).permute_axes([0, 0]);
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Sums over one finite axis, giving a tensor with one dimension less.
+    ///
+    /// The result has the ranges of this tensor with `axis` removed, and the
+    /// element at `idx` is the sum of the elements whose other indices are
+    /// `idx`. `M` must be `N - 1`. Panics if `M` is not `N - 1`, if `axis`
+    /// is out of bounds, or if the summed axis is infinite.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - The axis to sum over
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZTensor of rank `N - 1`
+    pub fn sum_axis<const M: usize>(&self, axis: usize) -> ZTensor<M> {
+        assert!(M + 1 == N, "sum_axis must return a tensor of rank {}, not {}", N - 1, M);
+        assert!(axis < N, "sum_axis: axis {} out of bounds for a {}-dimensional tensor", axis, N);
+        let summed = contraction_range(&self.index_ranges[axis], "sum_axis");
+        let ranges: [Range<OmegaIndex>; M] = std::array::from_fn(|d| {
+            self.index_ranges[if d < axis { d } else { d + 1 }].clone()
+        });
+        let valget = self.value_getter.clone();
+        ZTensor::<M>::from_ranges_values(&ranges, move |idx: &[FiniteIndex; M]|{
+            summed.clone().map(|k| {
+                let source: [FiniteIndex; N] = std::array::from_fn(|d| {
+                    match d.cmp(&axis) {
+                        std::cmp::Ordering::Less => idx[d],
+                        std::cmp::Ordering::Equal => k,
+                        std::cmp::Ordering::Greater => idx[d - 1]
+                    }
+                });
+                valget(&source)
+            }).sum()
+        })
+    }
+}
+
+impl ZMatrix {
+    /// Sums the rows together, giving the sum of each column.
+    ///
+    /// The result is indexed by the columns. Panics if the rows are infinite.
+    pub fn sum_rows(&self) -> ZVector {
+        self.sum_axis(0)
+    }
+
+    /// Sums the columns together, giving the sum of each row.
+    ///
+    /// The result is indexed by the rows. Panics if the columns are infinite.
+    pub fn sum_cols(&self) -> ZVector {
+        self.sum_axis(1)
+    }
+}
+
+#[test]
+fn test_sum_axis(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZTensor::<3>::from_ranges_values(&[Integer(0)..Integer(2), MOmega..POmega, Integer(1)..Integer(4)], |&[a, b, c]|{
+        Elem::new((100 * a + c) as f32, b as f32)
+    });
+    let reduced: ZMatrix = t.sum_axis(2);
+    assert_eq!(reduced.get_index_ranges(), [Integer(0)..Integer(2), MOmega..POmega]);
+    assert_eq!(reduced.get_single_elem(&[1, -5]), Elem::new(306.0, -15.0));
+    let reduced: ZMatrix = t.sum_axis(0);
+    assert_eq!(reduced.get_index_ranges(), [MOmega..POmega, Integer(1)..Integer(4)]);
+    assert_eq!(reduced.get_single_elem(&[7, 2]), Elem::new(104.0, 14.0));
+}
+
+#[test]
+#[should_panic(expected = "sum_axis requires a finite contraction range")]
+fn test_sum_axis_infinite(){
+    use super::omega_int::OmegaInt::*;
+    let _: ZVector = ZMatrix::zeros(&[Integer(0)..Integer(2), Integer(0)..POmega]).sum_axis(1);
+}
+
+impl<const N: usize, T> ZTensor<N, T> {
+    /// Sums all the elements of a finite tensor.
+    ///
+    /// Panics if any dimension is infinite.
+    ///
+    /// # Returns
+    ///
+    /// The sum of the elements, which is zero for an empty tensor
+    pub fn sum(&self) -> T where T: Sum {
+        self.iter_indexed().map(|(_, value)| value).sum()
+    }
+
+    /// Multiplies all the elements of a finite tensor.
+    ///
+    /// Panics if any dimension is infinite.
+    ///
+    /// # Returns
+    ///
+    /// The product of the elements, which is one for an empty tensor
+    pub fn product(&self) -> T where T: Product {
+        self.iter_indexed().map(|(_, value)| value).product()
+    }
+}
+
+impl<const N: usize> ZTensor<N> {
+    /// Computes the mean of all the elements of a finite tensor.
+    ///
+    /// Panics if any dimension is infinite.
+    ///
+    /// # Returns
+    ///
+    /// The mean of the elements, which is NaN for an empty tensor
+    pub fn mean(&self) -> Elem {
+        let (count, sum) = self.iter_indexed().fold((0usize, Elem::zero()), |(count, sum), (_, value)| (count + 1, sum + value));
+        sum / count as f32
+    }
+}
+
+#[test]
+fn test_sum_mean_product(){
+    use super::omega_int::OmegaInt::*;
+    let t = ZMatrix::from_ranges_values(&[Integer(1)..Integer(3), Integer(-1)..Integer(1)], |&[i, j]| Elem::new(i as f32, j as f32));
+    // Elements are 1 - i, 1, 2 - i and 2
+    assert_eq!(t.sum(), Elem::new(6.0, -2.0));
+    assert_eq!(t.mean(), Elem::new(1.5, -0.5));
+    assert_eq!(t.product(), Elem::new(1.0, -1.0) * Elem::new(2.0, -1.0) * Elem::new(2.0, 0.0));
+    let empty = ZMatrix::zeros(&[Integer(0)..Integer(0), Integer(0)..Integer(3)]);
+    assert_eq!(empty.sum(), Elem::zero());
+    assert_eq!(empty.product(), Elem::one());
+}
+
+#[test]
+#[should_panic(expected = "ZTensor has an infinite dimension where a finite one is required")]
+fn test_sum_infinite(){
+    use super::omega_int::OmegaInt::*;
+    let _ = ZVector::zeros(&[Integer(0)..POmega]).sum();
+}
+
+impl ZVector {
+    /// Creates a vector whose elements are their own indices.
+    ///
+    /// The element at `i` is `i` with zero imaginary part. The bounds may be
+    /// infinite, since every index has a well-defined element.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - First index of the range
+    /// * `end` - End of the range, exclusive
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZVector over `start..end`
+    pub fn arange(start: OmegaIndex, end: OmegaIndex) -> ZVector {
+        ZVector::from_ranges_values(&[start..end], |&[i]| Elem::new(i as f32, 0.0))
+    }
+
+    /// Creates a vector of evenly spaced values between two endpoints.
+    ///
+    /// The result ranges over `0..n`, with the element at `0` equal to `a`
+    /// and, when `n > 1`, the element at `n - 1` equal to `b`.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The first value
+    /// * `b` - The last value
+    /// * `n` - Number of values
+    ///
+    /// # Returns
+    ///
+    /// A lazily evaluated ZVector of `n` values
+    pub fn linspace(a: Elem, b: Elem, n: usize) -> ZVector {
+        let step = if n > 1 { (b - a) / (n - 1) as f32 } else { Elem::zero() };
+        ZVector::from_ranges_values(&[OmegaIndex::Integer(0)..OmegaIndex::Integer(n as FiniteIndex)], move |&[i]|{
+            a + step * i as f32
+        })
+    }
+}
+
+impl<T: Clone + 'static> ZTensor<1, T> {
+    /// Creates a vector backed by a buffer of elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The elements, in order
+    ///
+    /// # Returns
+    ///
+    /// A ZVector over `0..data.len()`
+    pub fn from_vec(data: Vec<T>) -> Self {
+        let len = data.len();
+        Self::from_flat(data, [len])
+    }
+}
+
+#[test]
+fn test_arange_linspace_from_vec(){
+    use super::omega_int::OmegaInt::*;
+    let r = ZVector::arange(Integer(-2), Integer(3));
+    assert_eq!(r.numel(), Integer(5));
+    assert_eq!(r.get_single_elem(&[-2]), Elem::new(-2.0, 0.0));
+    assert_eq!(r.get_single_elem(&[2]), Elem::new(2.0, 0.0));
+    assert_eq!(ZVector::arange(Integer(0), POmega).get_single_elem(&[1000]), Elem::new(1000.0, 0.0));
+
+    let l = ZVector::linspace(Elem::new(1.0, 0.0), Elem::new(2.0, -4.0), 5);
+    assert_eq!(l.get_index_ranges(), [Integer(0)..Integer(5)]);
+    assert_eq!(l.get_single_elem(&[0]), Elem::new(1.0, 0.0));
+    assert_eq!(l.get_single_elem(&[2]), Elem::new(1.5, -2.0));
+    assert_eq!(l.get_single_elem(&[4]), Elem::new(2.0, -4.0));
+    assert_eq!(ZVector::linspace(Elem::one(), Elem::zero(), 1).to_vec(), (vec![Elem::one()], [1]));
+
+    let data = vec![Elem::new(1.0, 2.0), Elem::new(3.0, 4.0), Elem::new(5.0, 6.0)];
+    let v = ZVector::from_vec(data.clone());
+    assert_eq!(v.get_index_ranges(), [Integer(0)..Integer(3)]);
+    assert_eq!(v.get_single_elem(&[0]), data[0]);
+    assert_eq!(v.get_single_elem(&[2]), data[2]);
+    assert_eq!(v.backing(), BackingKind::Buffer);
+}
+
+/// Maximum number of elements printed by `preview` and the Debug implementation of ZTensor.
+const DEBUG_MAX_ELEMS: u64 = 100;
+
+/// Writes the cells of a row-major buffer as nested brackets.
+///
+/// The last dimension is written on one line, and the other dimensions
+/// are separated by line breaks, so matrices are printed as a grid. When
+/// the cells run out before the shape is filled, an elision marker is
+/// written and the open brackets are closed.
+///
+/// # Arguments
+///
+/// * `out` - The string to append to
+/// * `shape` - Length of each remaining dimension
+/// * `depth` - Number of enclosing brackets, used for indentation
+/// * `width` - Width to which each cell is right-aligned
+/// * `cells` - The formatted elements in row-major order
+///
+/// # Returns
+///
+/// False if the cells ran out and the rest was elided
+fn write_nested<'a>(out: &mut String, shape: &[usize], depth: usize, width: usize, cells: &mut impl Iterator<Item = &'a String>) -> bool {
+    let Some((&len, rest)) = shape.split_first() else {
+        return match cells.next() {
+            Some(cell) => {
+                out.push_str(&format!("{:>1$}", cell, width));
+                true
+            },
+            None => {
+                out.push_str("...");
+                false
+            }
+        };
+    };
+    out.push('[');
+    for k in 0..len {
+        if k > 0 {
+            if rest.is_empty() {
+                out.push_str(", ");
+            } else {
+                out.push_str(",\n");
+                out.push_str(&" ".repeat(depth + 1));
+            }
+        }
+        if !write_nested(out, rest, depth + 1, width, cells) {
+            out.push(']');
+            return false;
+        }
+    }
+    out.push(']');
+    true
+}
+
+impl<const N: usize, T: fmt::Display> ZTensor<N, T> {
+    /// Formats the elements of a finite window of this tensor.
+    ///
+    /// The window is intersected with the ranges of this tensor, and the
+    /// elements are printed as nested brackets with aligned columns, one row
+    /// per line for a matrix. At most `DEBUG_MAX_ELEMS` elements are
+    /// computed and printed, in row-major order, followed by `...` when the
+    /// window holds more. Empty dimensions print as empty, even with an
+    /// infinite endpoint. Panics if the intersected window is otherwise
+    /// infinite.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - Ranges of the window to print
+    ///
+    /// # Returns
+    ///
+    /// The formatted elements of the window
+    pub fn preview(&self, ranges: &[Range<OmegaIndex>; N]) -> String {
+        let window: [Range<OmegaIndex>; N] = std::array::from_fn(|d| {
+            let r = range_intersection(&ranges[d], &self.index_ranges[d]);
+            if r.start >= r.end { OmegaIndex::zero()..OmegaIndex::zero() } else { r }
+        });
+        let window = finite_ranges(&window);
+        let shape = window.clone().map(|r| (r.end - r.start).max(0) as usize);
+        let cells: Vec<String> = row_major_indices(window).take(DEBUG_MAX_ELEMS as usize).map(|idx| (self.value_getter)(&idx).to_string()).collect();
+        let width = cells.iter().map(|c| c.chars().count()).max().unwrap_or(0);
+        let mut out = String::new();
+        write_nested(&mut out, &shape, 0, width, &mut cells.iter());
+        out
+    }
+}
+
+/// Debug formatting of ZTensor.
+///
+/// Prints the ranges and backing kind, followed by the elements when the
+/// tensor is finite and has at most `DEBUG_MAX_ELEMS` elements.
+impl<const N: usize, T: fmt::Display> fmt::Debug for ZTensor<N, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranges: Vec<String> = self.index_ranges.iter().map(|r| format!("{}..{}", r.start, r.end)).collect();
+        write!(f, "ZTensor {{ index_ranges: [{}], backing: {:?} }}", ranges.join(", "), self.backing)?;
+        let numel = self.shape().iter().try_fold(1u64, |acc, l| l.to_finite().and_then(|l| acc.checked_mul(l)));
+        if numel.is_some_and(|n| n <= DEBUG_MAX_ELEMS) {
+            write!(f, "\n{}", self.preview(&self.index_ranges))?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_preview(){
+    use super::omega_int::OmegaInt::*;
+    let m = ZTensor::<2, i32>::from_flat(vec![1, -2, 3, 40, 5, 6], [2, 3]);
+    assert_eq!(m.preview(&m.get_index_ranges()), "[[ 1, -2,  3],\n [40,  5,  6]]");
+    assert_eq!(m.preview(&[Integer(1)..POmega, MOmega..Integer(2)]), "[[40,  5]]");
+    assert_eq!(format!("{:?}", m), "ZTensor { index_ranges: [0..2, 0..3], backing: Buffer }\n[[ 1, -2,  3],\n [40,  5,  6]]");
+
+    let v = ZVector::from_ranges_values(&[Integer(0)..POmega], |&[i]| Elem::new(i as f32, -1.0));
+    assert_eq!(v.preview(&[Integer(1)..Integer(3)]), "[1-1i, 2-1i]");
+    assert_eq!(format!("{:?}", v), "ZTensor { index_ranges: [0..ω], backing: Closure }");
+    let large = ZMatrix::zeros(&[Integer(0)..Integer(1000), Integer(0)..Integer(1000)]);
+    assert_eq!(format!("{:?}", large), "ZTensor { index_ranges: [0..1000, 0..1000], backing: Constant }");
+    let t3 = ZTensor::<3, i32>::from_flat((0..8).collect(), [2, 2, 2]);
+    assert_eq!(t3.preview(&t3.get_index_ranges()), "[[[0, 1],\n  [2, 3]],\n [[4, 5],\n  [6, 7]]]");
+}
+
+#[test]
+fn test_preview_caps_elements(){
+    use super::omega_int::OmegaInt::*;
+    use std::cell::Cell;
+    let calls = Rc::new(Cell::new(0));
+    let calls_in_getter = calls.clone();
+    let m = ZTensor::<2, i64>::from_ranges_values(&[Integer(0)..Integer(10_000), Integer(0)..Integer(10_000)], move |&[i, j]|{
+        calls_in_getter.set(calls_in_getter.get() + 1);
+        i * 10_000 + j
+    });
+    let preview = m.preview(&m.get_index_ranges());
+    assert_eq!(calls.get(), DEBUG_MAX_ELEMS);
+    assert!(preview.starts_with("[[ 0,  1,  2,"));
+    assert!(preview.ends_with("98, 99, ...]]"));
+    let v = ZTensor::<1, i64>::from_ranges_values(&[Integer(0)..Integer(101)], |&[i]| i % 10);
+    assert_eq!(v.preview(&v.get_index_ranges()).matches(", ").count(), DEBUG_MAX_ELEMS as usize);
+    let m = ZTensor::<2, i64>::from_ranges_values(&[Integer(0)..Integer(60), Integer(0)..Integer(2)], |_| 0);
+    assert!(m.preview(&m.get_index_ranges()).ends_with(",\n [...]]"));
+}
+
+#[test]
+fn test_debug_empty_infinite_endpoints(){
+    use super::omega_int::OmegaInt::*;
+    let at_pomega = ZTensor::<1>::zeros(&[POmega..POmega]);
+    assert_eq!(format!("{:?}", at_pomega), "ZTensor { index_ranges: [ω..ω], backing: Constant }\n[]");
+    let at_momega = ZTensor::<1>::zeros(&[MOmega..MOmega]);
+    assert_eq!(format!("{:?}", at_momega), "ZTensor { index_ranges: [-ω..-ω], backing: Constant }\n[]");
+}
@@ -1,6 +1,10 @@
-use std::ops::Range;
+use std::fmt;
+use std::ops::{Neg, Range};
 use super::omega_int;
+use super::omega_uint::OmegaUInt;
 use dyn_clone::DynClone;
+use num_complex::Complex;
+use num_traits::Num;
 
 /// Type alias for finite indices used in ZTensors.
 /// Uses 64-bit integers to represent finite index values.
@@ -23,6 +27,45 @@ where
 
 dyn_clone::clone_trait_object!(<const N: usize, Elem> CloneableFn<N, Elem>);
 
+/// Trait for cloneable functions that can be shared across threads.
+///
+/// This is the thread-safe counterpart of CloneableFn, needed when elements
+/// are computed in parallel.
+pub trait SyncCloneableFn<const N:usize, Elem>: CloneableFn<N, Elem> + Send + Sync {}
+
+/// Implementation of SyncCloneableFn for any thread-safe CloneableFn.
+impl<const N:usize, Elem, F> SyncCloneableFn<N, Elem> for F
+where
+    F: CloneableFn<N, Elem> + Send + Sync {}
+
+dyn_clone::clone_trait_object!(<const N: usize, Elem> SyncCloneableFn<N, Elem>);
+
+/// Trait for element types that have a complex conjugate.
+///
+/// Real numbers are their own conjugate.
+pub trait Conjugate {
+    /// Returns the complex conjugate of this value.
+    fn conjugate(&self) -> Self;
+}
+
+impl<F: Clone + Num + Neg<Output = F>> Conjugate for Complex<F> {
+    fn conjugate(&self) -> Self {
+        self.conj()
+    }
+}
+
+macro_rules! impl_conjugate_real {
+    ($($t:ty),*) => {
+        $(impl Conjugate for $t {
+            fn conjugate(&self) -> Self {
+                *self
+            }
+        })*
+    };
+}
+
+impl_conjugate_real!(f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
 /// Core trait for tensor-like objects with N dimensions.
 ///
 /// This trait defines the fundamental operations for accessing elements and
@@ -48,6 +91,33 @@ pub trait ZTensorLike<const N: usize> {
     ///
     /// Array of ranges defining the extents of each dimension
     fn get_index_ranges(&self) -> [Range<OmegaIndex>; N];
+
+    /// Checks whether every dimension has finite endpoints.
+    ///
+    /// # Returns
+    ///
+    /// True if all range endpoints are finite integers
+    fn all_dims_finite(&self) -> bool {
+        self.get_index_ranges().iter().all(|r| r.start.is_finite() && r.end.is_finite())
+    }
+
+    /// Returns the length of each dimension.
+    ///
+    /// Empty ranges have length 0, and nonempty ranges with an infinite
+    /// endpoint have length Omega.
+    ///
+    /// # Returns
+    ///
+    /// Array of lengths, one for each dimension
+    fn shape(&self) -> [OmegaUInt<u64>; N] {
+        self.get_index_ranges().map(|r| {
+            match (r.start, r.end) {
+                (start, end) if start >= end => OmegaUInt::Natural(0),
+                (omega_int::Integer(start), omega_int::Integer(end)) => OmegaUInt::Natural(end.abs_diff(start)),
+                _ => OmegaUInt::Omega
+            }
+        })
+    }
 }
 
 /// Trait for tensor-like objects that can be created from ranges and a value function.
@@ -66,6 +136,85 @@ pub trait ZTensorLikeFromRangesValues<const N:usize> : ZTensorLike<N> {
     ///
     /// A new tensor with the specified configuration
     fn from_ranges_values<F: CloneableFn<N, Self::DType> + 'static>(ranges: &[Range<OmegaIndex>; N], value_getter: F) -> Self;
+
+    /// Creates a new tensor after validating the ranges.
+    ///
+    /// The ranges are checked with `canonicalize_ranges` before the tensor
+    /// is built.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - Array of ranges for each dimension
+    /// * `value_getter` - Function that computes the tensor element for given indices
+    ///
+    /// # Returns
+    ///
+    /// The new tensor, or the error describing the first invalid range
+    fn try_from_ranges_values<F: CloneableFn<N, Self::DType> + 'static>(ranges: &[Range<OmegaIndex>; N], value_getter: F) -> Result<Self, RangeError> where Self: Sized {
+        let ranges = canonicalize_ranges(ranges)?;
+        Ok(Self::from_ranges_values(&ranges, value_getter))
+    }
+}
+
+/// Error describing an invalid tensor range.
+///
+/// Each variant carries the axis of the offending range.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RangeError {
+    /// The range starts at positive infinity
+    StartsAtPOmega(usize),
+    /// The range ends at negative infinity
+    EndsAtMOmega(usize),
+    /// The range starts after it ends
+    Reversed(usize)
+}
+
+impl RangeError {
+    /// Returns the axis of the offending range.
+    pub fn axis(&self) -> usize {
+        match self {
+            RangeError::StartsAtPOmega(axis) | RangeError::EndsAtMOmega(axis) | RangeError::Reversed(axis) => *axis
+        }
+    }
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::StartsAtPOmega(axis) => write!(f, "range of axis {} starts at positive infinity", axis),
+            RangeError::EndsAtMOmega(axis) => write!(f, "range of axis {} ends at negative infinity", axis),
+            RangeError::Reversed(axis) => write!(f, "range of axis {} starts after it ends", axis)
+        }
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+/// Validates tensor ranges.
+///
+/// Rejects ranges starting at `POmega`, ending at `MOmega`, or starting
+/// after they end. Empty ranges with `start == end` are allowed.
+///
+/// # Arguments
+///
+/// * `ranges` - Array of ranges for each dimension
+///
+/// # Returns
+///
+/// The validated ranges, or the error for the first invalid axis
+pub fn canonicalize_ranges<const N: usize>(ranges: &[Range<OmegaIndex>; N]) -> Result<[Range<OmegaIndex>; N], RangeError> {
+    for (axis, r) in ranges.iter().enumerate() {
+        if r.start == OmegaIndex::POmega {
+            return Err(RangeError::StartsAtPOmega(axis));
+        }
+        if r.end == OmegaIndex::MOmega {
+            return Err(RangeError::EndsAtMOmega(axis));
+        }
+        if r.start > r.end {
+            return Err(RangeError::Reversed(axis));
+        }
+    }
+    Ok(ranges.clone())
 }
 
 /// Converts a range from one type to another using Into trait.
@@ -81,6 +230,41 @@ pub fn range_into<S, T>(r: Range<S>) -> Range<T> where S: Into<T> {
     Range { start: r.start.into(), end: r.end.into() }
 }
 
+/// Checks whether a finite index lies within a possibly infinite range.
+///
+/// # Arguments
+///
+/// * `range` - Range whose endpoints may be infinite
+/// * `index` - Finite index to test
+///
+/// # Returns
+///
+/// True if `range.start <= index < range.end`
+pub fn omega_range_contains(range: &Range<OmegaIndex>, index: FiniteIndex) -> bool {
+    range.start <= OmegaIndex::Integer(index) && OmegaIndex::Integer(index) < range.end
+}
+
+/// Computes the intersection of two possibly infinite ranges.
+///
+/// # Arguments
+///
+/// * `a` - First range
+/// * `b` - Second range
+///
+/// # Returns
+///
+/// The overlap of both ranges, or an empty range starting at the
+/// larger start if they do not overlap
+pub fn range_intersection(a: &Range<OmegaIndex>, b: &Range<OmegaIndex>) -> Range<OmegaIndex> {
+    let start = a.start.max(b.start);
+    let end = a.end.min(b.end);
+    if start <= end {
+        start..end
+    } else {
+        start..start
+    }
+}
+
 /// Trait for tensor-like objects that support slicing.
 ///
 /// This trait allows extracting sub-tensors by specifying ranges
@@ -88,6 +272,9 @@ pub fn range_into<S, T>(r: Range<S>) -> Range<T> where S: Into<T> {
 pub trait ZTensorLikeSlice<const N:usize> : ZTensorLike<N> {
     /// Creates a slice of this tensor with the specified ranges.
     ///
+    /// The slice covers the intersection of the requested ranges with the
+    /// ranges of this tensor, so it never reads outside of them.
+    ///
     /// # Arguments
     ///
     /// * `ranges` - Array of ranges to slice each dimension
@@ -126,12 +313,30 @@ impl<T, const N: usize> ZTensorLikeSliceGenericIndex<N> for T where
 /// Default implementation of ZTensorLikeSlice for any tensor type that satisfies the requirements.
 impl<const N: usize, TS: ZTensorLike<N> + ZTensorLikeFromRangesValues<N> + Clone + 'static> ZTensorLikeSlice<N> for TS {
     fn get_slice(&self, ranges: &[Range<OmegaIndex>; N]) -> Self {
+        let parent_ranges = self.get_index_ranges();
+        let ranges: [Range<OmegaIndex>; N] = std::array::from_fn(|d| range_intersection(&ranges[d], &parent_ranges[d]));
         let self2 = (*self).clone();
         let eval_closure = move |indices: &[FiniteIndex; N]|{self2.get_single_elem(indices)};
-        TS::from_ranges_values(ranges, eval_closure)
+        TS::from_ranges_values(&ranges, eval_closure)
     }
 }
 
+#[test]
+fn test_get_slice_intersects_parent_ranges(){
+    use super::omega_int::OmegaInt::*;
+    use super::ztensor_impls::{Elem, ZMatrix, ZVector};
+    let v = ZVector::from_ranges_values(&[Integer(0)..Integer(3)], |&[i]| Elem::new(i as f32, 0.0));
+    let slice = v.get_slice(&[Integer(1)..Integer(10)]);
+    assert_eq!(slice.get_index_ranges(), [Integer(1)..Integer(3)]);
+    assert_eq!(slice.get_single_elem(&[2]), Elem::new(2.0, 0.0));
+
+    let m = ZMatrix::zeros(&[Integer(0)..POmega, MOmega..Integer(5)]);
+    let slice = m.get_slice(&[MOmega..Integer(4), Integer(-2)..POmega]);
+    assert_eq!(slice.get_index_ranges(), [Integer(0)..Integer(4), Integer(-2)..Integer(5)]);
+    let disjoint = v.get_slice(&[Integer(5)..Integer(8)]);
+    assert!(disjoint.get_index_ranges()[0].start == disjoint.get_index_ranges()[0].end);
+}
+
 /// Trait for tensor-like objects that can be created from ranges with generic index types.
 ///
 /// This trait extends creation functionality to work with any index type
@@ -175,3 +380,47 @@ pub trait ZMatrixLike : ZTensorLike<2> {}
 
 /// Blanket implementation of ZMatrixLike for all 2-dimensional tensor types.
 impl<T: ZTensorLike<2>> ZMatrixLike for T {}
+
+#[test]
+fn test_canonicalize_ranges(){
+    use super::omega_int::OmegaInt::*;
+    let valid = [MOmega..POmega, Integer(-3)..POmega, Integer(2)..Integer(2)];
+    assert_eq!(canonicalize_ranges(&valid), Ok(valid.clone()));
+    assert_eq!(canonicalize_ranges(&[Integer(0)..Integer(1), POmega..POmega]), Err(RangeError::StartsAtPOmega(1)));
+    assert_eq!(canonicalize_ranges(&[MOmega..MOmega]), Err(RangeError::EndsAtMOmega(0)));
+    assert_eq!(canonicalize_ranges(&[Integer(0)..Integer(1), Integer(0)..Integer(1), Integer(5)..Integer(2)]).unwrap_err().axis(), 2);
+
+    use super::ztensor_impls::{Elem, ZMatrix};
+    let t = ZMatrix::try_from_ranges_values(&[MOmega..POmega, Integer(0)..Integer(3)], |_|{Elem::new(1.0, 0.0)});
+    assert!(t.is_ok());
+    let t = ZMatrix::try_from_ranges_values(&[POmega..POmega, Integer(0)..Integer(3)], |_|{Elem::new(1.0, 0.0)});
+    assert!(matches!(t, Err(RangeError::StartsAtPOmega(0))));
+}
+
+#[test]
+fn test_shape(){
+    use super::omega_int::OmegaInt::*;
+    use super::omega_uint::OmegaUInt::*;
+    use super::ztensor_impls::ZTensor;
+    let t = ZTensor::<3>::zeros(&[Integer(-2)..Integer(5), Integer(0)..POmega, Integer(4)..Integer(1)]);
+    assert_eq!(t.shape(), [Natural(7), Omega, Natural(0)]);
+    let t = ZTensor::<3>::zeros(&[MOmega..POmega, MOmega..Integer(0), POmega..POmega]);
+    assert_eq!(t.shape(), [Omega, Omega, Natural(0)]);
+    let t = ZTensor::<1>::zeros(&[Integer(FiniteIndex::MIN)..Integer(FiniteIndex::MAX)]);
+    assert_eq!(t.shape(), [Natural(u64::MAX)]);
+}
+
+#[test]
+fn test_all_dims_finite(){
+    use super::omega_int::OmegaInt::*;
+    use super::ztensor_impls::{Elem, ZMatrix};
+    fn is_finite<T: ZTensorLike<2>>(t: &T) -> bool {
+        t.all_dims_finite()
+    }
+    let finite = ZMatrix::from_ranges_values(&[Integer(0)..Integer(3), Integer(-2)..Integer(2)], |_|{Elem::new(0.0, 0.0)});
+    let infinite = ZMatrix::from_ranges_values(&[Integer(0)..Integer(3), Integer(-2)..POmega], |_|{Elem::new(0.0, 0.0)});
+    assert!(is_finite(&finite));
+    assert!(!is_finite(&infinite));
+    let object: &dyn ZTensorLike<2, DType = Elem> = &infinite;
+    assert!(!object.all_dims_finite());
+}